@@ -0,0 +1,193 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use libutils::input::InputSource;
+use libutils::log;
+use libutils::solver::{Answers, DynSolver};
+
+const USAGE: &str = "Usage: aoc run (<day>... | --all) [--input <file name>] [--log <level>]
+       aoc run <day> --file <path or - for stdin> [--log <level>]
+
+By default each day reads day<N>/input.txt; --input picks another file in the
+day's directory. Day directories are looked up in the current directory, then in
+the workspace the binary was built from. When running a single day with neither
+--input nor --file, AOC_INPUT can point to its input.
+--log (or AOC_LOG) is one of error, info, debug, trace; the default is error.";
+
+fn solvers() -> Vec<Box<dyn DynSolver>> {
+    vec![
+        Box::new(day1::Day1),
+        Box::new(day2::Day2),
+        Box::new(day3::Day3),
+        Box::new(day4::Day4),
+        Box::new(day5::Day5),
+        Box::new(day6::Day6),
+        Box::new(day7::Day7),
+    ]
+}
+
+struct RunArgs {
+    days: Vec<u32>,
+    all: bool,
+    input_name: Option<String>,
+    file: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<RunArgs, String> {
+    let mut args = args.iter();
+    match args.next().map(|s| s.as_str()) {
+        Some("run") => {}
+        Some(other) => return Err(format!("Unknown command '{}'", other)),
+        None => return Err("Missing command".to_string()),
+    }
+
+    let mut run_args = RunArgs {
+        days: vec![],
+        all: false,
+        input_name: None,
+        file: None,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => run_args.all = true,
+            "--input" => {
+                run_args.input_name =
+                    Some(args.next().ok_or("--input requires a file name")?.clone());
+            }
+            "--log" => {
+                let level = args.next().ok_or("--log requires a level")?;
+                log::set_level(level.parse()?);
+            }
+            "--file" => {
+                run_args.file = Some(args.next().ok_or("--file requires a path")?.clone());
+            }
+            day => run_args
+                .days
+                .push(day.parse().map_err(|_| format!("Invalid day '{}'", day))?),
+        }
+    }
+
+    if !run_args.all && run_args.days.is_empty() {
+        return Err("Specify at least one day, or --all".to_string());
+    }
+    if run_args.file.is_some() && (run_args.all || run_args.days.len() > 1) {
+        return Err("--file can only be used when running a single day".to_string());
+    }
+    Ok(run_args)
+}
+
+fn print_table(rows: &[Answers]) {
+    let headers = ["Day", "Part 1", "Part 2", "Time"];
+    let cells: Vec<[String; 4]> = rows
+        .iter()
+        .map(|row| {
+            [
+                row.day.to_string(),
+                row.part1.clone(),
+                row.part2.clone(),
+                format!("{:.2?}", row.elapsed),
+            ]
+        })
+        .collect();
+
+    let mut widths = headers.map(|h| h.len());
+    for row in cells.iter() {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    let format_row = |row: [&str; 4]| {
+        row.iter()
+            .enumerate()
+            .map(|(i, cell)| format!("{:<width$}", cell, width = widths[i]))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+    println!("{}", format_row(headers));
+    println!(
+        "{}",
+        widths
+            .iter()
+            .map(|w| "-".repeat(*w))
+            .collect::<Vec<_>>()
+            .join("-+-")
+    );
+    for row in cells.iter() {
+        println!("{}", format_row([&row[0], &row[1], &row[2], &row[3]]));
+    }
+}
+
+/// `day<N>` in the current directory if there is one, otherwise in the workspace this binary
+/// was built from (a path on the build machine, fine for a binary run from its checkout).
+fn day_dir(day: u32) -> PathBuf {
+    let relative = PathBuf::from(format!("day{}", day));
+    if relative.is_dir() {
+        relative
+    } else {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join(relative)
+    }
+}
+
+fn main() -> ExitCode {
+    log::init_from_env();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let run_args = match parse_args(&args) {
+        Ok(run_args) => run_args,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let solvers = solvers();
+    let selected: Vec<&Box<dyn DynSolver>> = if run_args.all {
+        solvers.iter().collect()
+    } else {
+        let mut selected = vec![];
+        for day in run_args.days.iter() {
+            match solvers.iter().find(|solver| solver.day() == *day) {
+                Some(solver) => selected.push(solver),
+                None => {
+                    eprintln!("No solver registered for day {}", day);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        selected
+    };
+
+    let mut rows = vec![];
+    let mut failed = false;
+    let single_day = selected.len() == 1;
+    for solver in selected {
+        let input_name = run_args.input_name.as_deref().unwrap_or("input.txt");
+        let default = day_dir(solver.day()).join(input_name);
+        let default = default.to_string_lossy();
+        let input = match (&run_args.file, &run_args.input_name) {
+            (Some(file), _) => InputSource::from_arg(file),
+            (None, None) if single_day => InputSource::resolve(None, &default),
+            (None, _) => InputSource::from_arg(&default),
+        };
+        match solver.solve(&input) {
+            Ok(answers) => rows.push(answers),
+            Err(err) => {
+                eprintln!("Day {} failed on {}: {}", solver.day(), input, err);
+                failed = true;
+            }
+        }
+    }
+
+    if !rows.is_empty() {
+        print_table(&rows);
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,67 @@
+use libutils::{
+    frequencies, input::InputSource, parser::parse_all, peekable_string, read_file_map_lines,
+    solver::Solver, FileProcessingErr,
+};
+use thiserror::Error;
+
+pub struct Day1;
+
+libutils::answer_tests!(Day1);
+
+impl Solver for Day1 {
+    type Input = (Vec<i32>, Vec<i32>);
+    type Output = i32;
+    type Error = FileProcessingErr<ParseError>;
+
+    fn day(&self) -> u32 {
+        1
+    }
+
+    fn parse(&self, input: &InputSource) -> Result<Self::Input, Self::Error> {
+        let numbers = read_file_map_lines(input, &mut parse_line)?;
+        let mut list1 = vec![];
+        let mut list2 = vec![];
+        for (num1, num2) in numbers.into_iter() {
+            list1.push(num1);
+            list2.push(num2);
+        }
+
+        list1.sort();
+        list2.sort();
+        Ok((list1, list2))
+    }
+
+    fn part1(&self, (list1, list2): &Self::Input) -> i32 {
+        let mut sum_of_differences = 0;
+        for i in 0..list1.len() {
+            let diff = list1[i] - list2[i];
+            sum_of_differences += if diff > 0 { diff } else { -diff };
+        }
+        sum_of_differences
+    }
+
+    fn part2(&self, (list1, list2): &Self::Input) -> i32 {
+        let list2_freqs = frequencies(list2.clone());
+        let mut similarity_score = 0;
+        for i in list1.iter() {
+            similarity_score += i * (*list2_freqs.get(i).unwrap_or(&0) as i32);
+        }
+        similarity_score
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ParseError {
+    #[error("Parsing error: {0}")]
+    ParseError(#[from] peekable_string::ParseError),
+}
+
+fn parse_line(str: String) -> Result<(i32, i32), ParseError> {
+    Ok(parse_all(&str, |input| {
+        let first = input.parse_int()?;
+        input.take_while1(char::is_whitespace, "whitespace")?;
+        let second = input.parse_int()?;
+        input.skip_whitespace();
+        Ok((first, second))
+    })?)
+}
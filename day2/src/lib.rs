@@ -0,0 +1,111 @@
+use libutils::{
+    input::InputSource,
+    parser::{parse_all, separated_list},
+    peekable_string::{self, PeekableString},
+    read_file_map_lines,
+    solver::Solver,
+    FileProcessingErr,
+};
+use thiserror::Error;
+
+pub struct Day2;
+
+libutils::answer_tests!(Day2);
+
+impl Solver for Day2 {
+    type Input = Vec<Vec<i32>>;
+    type Output = usize;
+    type Error = FileProcessingErr<ParseError>;
+
+    fn day(&self) -> u32 {
+        2
+    }
+
+    fn parse(&self, input: &InputSource) -> Result<Self::Input, Self::Error> {
+        read_file_map_lines(input, &mut parse_line)
+    }
+
+    fn part1(&self, input: &Self::Input) -> usize {
+        input.iter().filter(|list| is_safe(list)).count()
+    }
+
+    fn part2(&self, input: &Self::Input) -> usize {
+        let mut safe_lists = vec![];
+        for list in input.iter() {
+            if find_with_list_permutations(list, |item| {
+                if is_safe(item) {
+                    Some(item.len())
+                } else {
+                    None
+                }
+            })
+            .is_some()
+            {
+                safe_lists.push(list);
+            }
+        }
+        safe_lists.len()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ParseError {
+    #[error("Parsing error: {0}")]
+    ParseError(#[from] peekable_string::ParseError),
+}
+
+fn parse_line(str: String) -> Result<Vec<i32>, ParseError> {
+    Ok(parse_all(&str, |input| {
+        separated_list(input, PeekableString::parse_int, |input| {
+            input
+                .take_while1(char::is_whitespace, "whitespace")
+                .map(|_| ())
+        })
+    })?)
+}
+
+fn is_safe(list: &Vec<i32>) -> bool {
+    let decreasing = (list.first().unwrap() - list.last().unwrap()) > 0;
+    let mut prev_number: Option<i32> = None;
+    let mut is_safe = true;
+
+    for number in list {
+        match prev_number {
+            None => {
+                prev_number = Some(*number);
+            }
+            Some(prev) if (decreasing && prev > *number && prev <= number + 3) => {
+                prev_number = Some(*number);
+            }
+            Some(prev) if (!decreasing && prev < *number && prev >= number - 3) => {
+                prev_number = Some(*number);
+            }
+            _ => {
+                is_safe = false;
+                break;
+            }
+        }
+    }
+    is_safe
+}
+
+pub fn find_with_list_permutations<T, TRes>(
+    vector: &std::vec::Vec<T>,
+    check: fn(&Vec<T>) -> Option<TRes>,
+) -> Option<TRes>
+where
+    T: Clone + 'static,
+    TRes: 'static,
+{
+    if let Some(res) = check(vector) {
+        return Some(res);
+    }
+    for i in 0..vector.len() {
+        let mut new_arr = (*vector).clone();
+        new_arr.remove(i);
+        if let Some(res) = check(&new_arr) {
+            return Some(res);
+        }
+    }
+    None
+}
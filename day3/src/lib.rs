@@ -0,0 +1,144 @@
+use libutils::{
+    input::InputSource,
+    lexer::{Lexer, Token, TokenKind},
+    read_file_to_str,
+    solver::Solver,
+};
+
+pub struct Day3;
+
+libutils::answer_tests!(Day3);
+
+impl Solver for Day3 {
+    type Input = Vec<Expr>;
+    type Output = i32;
+    type Error = std::io::Error;
+
+    fn day(&self) -> u32 {
+        3
+    }
+
+    fn parse(&self, input: &InputSource) -> Result<Self::Input, Self::Error> {
+        let input = read_file_to_str(input)?;
+        Ok(parse_muls(&input))
+    }
+
+    fn part1(&self, input: &Self::Input) -> i32 {
+        // ignore the do/don't to get back to part 1
+        let mut input: Vec<Expr> = input.clone();
+        input.retain(|expr| *expr != Expr::Do && *expr != Expr::Dont);
+        add_up(&input)
+    }
+
+    fn part2(&self, input: &Self::Input) -> i32 {
+        add_up(input)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Expr {
+    Mul(i32, i32),
+    Do,
+    Dont,
+}
+
+fn parse_muls(input: &str) -> Vec<Expr> {
+    let lexer = Lexer::new()
+        .keywords(&["mul", "do", "don't"])
+        .punctuation(&["(", ")", ","])
+        .integers();
+    let tokens: Vec<Token> = lexer.tokenize(input).collect();
+    let mut result = Vec::new();
+    let mut idx = 0;
+    while idx < tokens.len() {
+        match match_instruction(&tokens[idx..]) {
+            Some((expr, token_count)) => {
+                result.push(expr);
+                idx += token_count;
+            }
+            // garbage: skip it and try again on the next token
+            None => idx += 1,
+        }
+    }
+    result
+}
+
+/// Matches an instruction at the start of `tokens`, returning it and how many tokens it used.
+fn match_instruction(tokens: &[Token]) -> Option<(Expr, usize)> {
+    use TokenKind::{Integer, Keyword, Punctuation};
+
+    // instructions can't have any noise in between their tokens
+    let mut kinds = vec![];
+    for (i, token) in tokens.iter().enumerate().take(6) {
+        if i > 0 && !token.follows(&tokens[i - 1]) {
+            break;
+        }
+        kinds.push(token.kind);
+    }
+
+    match kinds.as_slice() {
+        [Keyword("mul"), Punctuation("("), Integer(a), Punctuation(","), Integer(b), Punctuation(")"), ..] => {
+            Some((Expr::Mul((*a).try_into().ok()?, (*b).try_into().ok()?), 6))
+        }
+        [Keyword("do"), Punctuation("("), Punctuation(")"), ..] => Some((Expr::Do, 3)),
+        [Keyword("don't"), Punctuation("("), Punctuation(")"), ..] => Some((Expr::Dont, 3)),
+        _ => None,
+    }
+}
+
+fn add_up(input: &Vec<Expr>) -> i32 {
+    let mut result = 0;
+    let mut mul_enabled = true;
+    for i in input {
+        match i {
+            Expr::Mul(a, b) if mul_enabled => result += a * b,
+            Expr::Mul(_, _) => {}
+            Expr::Do => mul_enabled = true,
+            Expr::Dont => mul_enabled = false,
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        add_up, parse_muls,
+        Expr::{Do, Dont, Mul},
+    };
+
+    #[test]
+    fn test_example() {
+        let res =
+            parse_muls("xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))");
+        assert_eq!(res, vec![Mul(2, 4), Mul(5, 5), Mul(11, 8), Mul(8, 5)]);
+    }
+
+    #[test]
+    fn test_example2() {
+        let res =
+            parse_muls("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))");
+        assert_eq!(
+            res,
+            vec![Mul(2, 4), Dont, Mul(5, 5), Mul(11, 8), Do, Mul(8, 5)]
+        );
+        assert_eq!(add_up(&res), 48);
+    }
+    #[test]
+    fn test_do_nothing_1() {
+        let res = parse_muls("mul(4*, mul(6,9!, ?(12,34)");
+        assert_eq!(res, vec![]);
+    }
+
+    #[test]
+    fn test_do_nothing_negative() {
+        let res = parse_muls("mul(-2,4)mul(2,-4)");
+        assert_eq!(res, vec![]);
+    }
+
+    #[test]
+    fn test_do_nothing_2() {
+        let res = parse_muls("mul ( 2 , 4 )");
+        assert_eq!(res, vec![]);
+    }
+}
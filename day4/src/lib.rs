@@ -0,0 +1,65 @@
+use std::convert::Infallible;
+
+use libutils::{
+    input::InputSource, map2d::Map2D, point::Direction, solver::Solver, FileProcessingErr,
+};
+
+pub struct Day4;
+
+libutils::answer_tests!(Day4);
+
+impl Solver for Day4 {
+    type Input = Map2D<Option<char>>;
+    type Output = i32;
+    type Error = FileProcessingErr<Infallible>;
+
+    fn day(&self) -> u32 {
+        4
+    }
+
+    fn parse(&self, input: &InputSource) -> Result<Self::Input, Self::Error> {
+        Map2D::from_file(input, None, |ch| Ok::<_, Infallible>(Some(ch)))
+    }
+
+    fn part1(&self, input: &Self::Input) -> i32 {
+        let mut part1_count = 0;
+
+        for start in input.coordinates_with_filter(|item| *item == Some('X')) {
+            for direction in input.directions() {
+                if input.matches_in_straight_direction(
+                    start,
+                    *direction,
+                    &[Some('M'), Some('A'), Some('S')],
+                ) {
+                    part1_count += 1
+                }
+            }
+        }
+        part1_count
+    }
+
+    fn part2(&self, input: &Self::Input) -> i32 {
+        let mut part2_count = 0;
+        for center in input.coordinates_with_filter(|input| *input == Some('A')) {
+            let diag_1_matches = matches!(
+                (
+                    input.get(center + Direction::NorthWest),
+                    input.get(center + Direction::SouthEast)
+                ),
+                (Some('M'), Some('S')) | (Some('S'), Some('M'))
+            );
+
+            let diag_2_matches = matches!(
+                (
+                    input.get(center + Direction::SouthWest),
+                    input.get(center + Direction::NorthEast)
+                ),
+                (Some('M'), Some('S')) | (Some('S'), Some('M'))
+            );
+            if diag_1_matches && diag_2_matches {
+                part2_count += 1;
+            }
+        }
+        part2_count
+    }
+}
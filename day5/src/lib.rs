@@ -0,0 +1,136 @@
+use std::collections::{HashMap, HashSet};
+
+use libutils::{
+    debug,
+    input::InputSource,
+    parser::{parse_all, separated_list},
+    peekable_string::{self, PeekableString},
+    sections::sections,
+    solver::Solver,
+    FileProcessingErr,
+};
+use thiserror::Error;
+
+pub struct Day5;
+
+libutils::answer_tests!(Day5);
+
+pub struct PrintQueue {
+    rules_precedes: HashMap<i32, HashSet<i32>>,
+    page_sequences: Vec<Vec<i32>>,
+}
+
+impl Solver for Day5 {
+    type Input = PrintQueue;
+    type Output = i32;
+    type Error = FileProcessingErr<ParseError>;
+
+    fn day(&self) -> u32 {
+        5
+    }
+
+    fn parse(&self, input: &InputSource) -> Result<Self::Input, Self::Error> {
+        let (rules, page_sequences) = sections(input).parse2(parse_rule, parse_page_list)?;
+        let mut rules_precedes = HashMap::new();
+        for (prev, next) in rules {
+            rules_precedes
+                .entry(prev)
+                .or_insert(HashSet::new())
+                .insert(next);
+        }
+        Ok(PrintQueue {
+            rules_precedes,
+            page_sequences,
+        })
+    }
+
+    fn part1(&self, input: &Self::Input) -> i32 {
+        let mut mid_sum = 0;
+        for sequence in input.page_sequences.iter() {
+            if is_valid(sequence, &input.rules_precedes) {
+                debug!("{sequence:?}");
+                mid_sum += sequence[sequence.len() / 2];
+            }
+        }
+        mid_sum
+    }
+
+    fn part2(&self, input: &Self::Input) -> i32 {
+        let mut mid_sum = 0;
+        for sequence in input.page_sequences.iter() {
+            if !is_valid(sequence, &input.rules_precedes) {
+                let fixed_sequence = fix_sequence(sequence.clone(), &input.rules_precedes);
+                debug!("{:?} -> {:?}", sequence, fixed_sequence);
+                mid_sum += fixed_sequence[fixed_sequence.len() / 2];
+            }
+        }
+        mid_sum
+    }
+}
+
+pub fn is_valid(sequence: &Vec<i32>, rules_precedes: &HashMap<i32, HashSet<i32>>) -> bool {
+    let mut seen_pages = HashSet::new();
+    for page in sequence {
+        match rules_precedes.get(page) {
+            Some(must_precede_these_pages)
+                if seen_pages
+                    .intersection(must_precede_these_pages)
+                    .next()
+                    .is_some() =>
+            {
+                return false
+            }
+            Some(_) => {}
+            None => {}
+        }
+        seen_pages.insert(*page);
+    }
+    true
+}
+
+pub fn fix_sequence(
+    mut sequence: Vec<i32>,
+    rules_precedes: &HashMap<i32, HashSet<i32>>,
+) -> Vec<i32> {
+    let mut did_swap = true;
+    while did_swap {
+        did_swap = false;
+        'outer_loop: for idx in 0..sequence.len() {
+            let page = sequence[idx];
+            if let Some(must_precede_these_pages) = rules_precedes.get(&page) {
+                for previous_page_idx in 0..idx {
+                    let prev_page = sequence[previous_page_idx];
+                    if must_precede_these_pages.contains(&prev_page) {
+                        sequence.swap(idx, previous_page_idx);
+                        did_swap = true;
+                        break 'outer_loop;
+                    }
+                }
+            }
+        }
+    }
+    sequence
+}
+
+#[derive(Error, Debug)]
+pub enum ParseError {
+    #[error("Parsing error: {0}")]
+    ParseError(#[from] peekable_string::ParseError),
+}
+
+/// Returns (number, successor)
+fn parse_rule(str: String) -> Result<(i32, i32), ParseError> {
+    Ok(parse_all(&str, |input| {
+        let prev = input.parse_int()?;
+        input.expect_str("|")?;
+        Ok((prev, input.parse_int()?))
+    })?)
+}
+
+fn parse_page_list(str: String) -> Result<Vec<i32>, ParseError> {
+    Ok(parse_all(&str, |input| {
+        separated_list(input, PeekableString::parse_int, |input| {
+            input.expect_str(",")
+        })
+    })?)
+}
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+
+use libutils::{
+    input::InputSource,
+    map2d::Map2D,
+    point::{Direction, Point},
+    solver::Solver,
+    trace, FileProcessingErr,
+};
+use thiserror::Error;
+
+pub struct Day6;
+
+libutils::answer_tests!(Day6);
+
+impl Solver for Day6 {
+    type Input = Map2D<MapCell>;
+    type Output = usize;
+    type Error = FileProcessingErr<ParseError>;
+
+    fn day(&self) -> u32 {
+        6
+    }
+
+    fn parse(&self, input: &InputSource) -> Result<Self::Input, Self::Error> {
+        Map2D::from_file(input, MapCell::OutOfMap, parse_cell)
+    }
+
+    fn part1(&self, input: &Self::Input) -> usize {
+        let (_, walked_path) = run_simulation(input.clone());
+        trace!(
+            "Guard walk:\n{}",
+            input
+                .render(MapCell::to_char)
+                .highlight(walked_path.iter().copied(), 'X')
+        );
+        walked_path.len()
+    }
+
+    fn part2(&self, input: &Self::Input) -> usize {
+        let (_, mut walked_path) = run_simulation(input.clone());
+        let guard_pos = input
+            .coordinates_with_filter(|cell| cell.is_guard())
+            .into_iter()
+            .next()
+            .unwrap();
+
+        walked_path.retain(|i| *i != guard_pos);
+
+        let mut looping_options = 0;
+        for obstacle in walked_path {
+            let mut modified_map = input.clone();
+            modified_map.set(obstacle, MapCell::Obstacle);
+            trace!("Run simulation with {}", obstacle);
+            let (is_loop, _) = run_simulation(modified_map);
+            if is_loop {
+                looping_options += 1;
+            }
+        }
+        looping_options
+    }
+}
+
+fn run_simulation(mut input: Map2D<MapCell>) -> (bool, Vec<Point>) {
+    let mut guard_pos = input
+        .coordinates_with_filter(|cell| cell.is_guard())
+        .into_iter()
+        .next()
+        .unwrap();
+
+    let MapCell::Guard(mut guard_direction) = input.get(guard_pos) else {
+        unreachable!()
+    };
+    let mut guard_historical_positions = HashSet::new();
+    let mut loop_detected = false;
+    while !input.get(guard_pos).is_out_of_map() {
+        input.set(guard_pos, MapCell::Visited);
+        if guard_historical_positions.contains(&(guard_direction, guard_pos)) {
+            loop_detected = true;
+            break;
+        }
+        guard_historical_positions.insert((guard_direction, guard_pos));
+
+        loop {
+            let next_guard_pos = guard_pos + guard_direction;
+            if input.get(next_guard_pos) == MapCell::Obstacle {
+                guard_direction = guard_direction.turn_right();
+                continue; // go back to checking the next guard position;
+            }
+            guard_pos = next_guard_pos;
+            break;
+        }
+    }
+
+    (
+        loop_detected,
+        input.coordinates_with_filter(|c| *c == MapCell::Visited),
+    )
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum MapCell {
+    Guard(Direction), // facing direction
+    Empty,
+    Obstacle,
+    Visited,
+    OutOfMap,
+}
+
+impl MapCell {
+    pub fn is_guard(&self) -> bool {
+        matches!(self, MapCell::Guard(_))
+    }
+    pub fn is_out_of_map(&self) -> bool {
+        matches!(self, MapCell::OutOfMap)
+    }
+
+    pub fn to_char(&self) -> char {
+        match self {
+            MapCell::Guard(direction) => direction.to_char(),
+            MapCell::Empty => '.',
+            MapCell::Obstacle => '#',
+            MapCell::Visited => 'X',
+            MapCell::OutOfMap => ' ',
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ParseError {
+    #[error("expected one of '^', '>', 'v', '<', '#', 'X' or '.'")]
+    UnknownCell,
+}
+
+fn parse_cell(ch: char) -> Result<MapCell, ParseError> {
+    match ch {
+        '^' | '>' | 'v' | '<' => Ok(MapCell::Guard(Direction::from_char(ch).unwrap())),
+        '#' => Ok(MapCell::Obstacle),
+        'X' => Ok(MapCell::Visited),
+        '.' => Ok(MapCell::Empty),
+        _ => Err(ParseError::UnknownCell),
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use libutils::{
+    dp_table::DpTable,
+    input::InputSource,
+    parser::{parse_all, separated_list},
+    peekable_string::{self, PeekableString},
+    read_file_map_lines,
+    solver::Solver,
+    trace, FileProcessingErr,
+};
+use thiserror::Error;
+
+pub struct Day7;
+
+libutils::answer_tests!(Day7);
+
+impl Solver for Day7 {
+    type Input = Vec<Problem>;
+    type Output = i64;
+    type Error = FileProcessingErr<ParseError>;
+
+    fn day(&self) -> u32 {
+        7
+    }
+
+    fn parse(&self, input: &InputSource) -> Result<Self::Input, Self::Error> {
+        read_file_map_lines(input, &mut parse_line)
+    }
+
+    fn part1(&self, input: &Self::Input) -> i64 {
+        calibration_value(input, false)
+    }
+
+    fn part2(&self, input: &Self::Input) -> i64 {
+        calibration_value(input, true)
+    }
+}
+
+fn calibration_value(problems: &[Problem], with_concatenation: bool) -> i64 {
+    let mut calibration_value = 0;
+    for problem in problems.iter() {
+        let possible_values = try_combinations(
+            problem.inputs.as_slice(),
+            problem.target,
+            with_concatenation,
+        );
+        trace!(
+            "With problem {:?} i have target {:?} and got {:?} possible results",
+            problem.inputs,
+            problem.target,
+            possible_values.len()
+        );
+        if possible_values.contains(&problem.target) {
+            trace!("Ok!");
+            calibration_value += problem.target
+        }
+    }
+    calibration_value
+}
+
+pub struct Problem {
+    target: i64,
+    inputs: Vec<i64>,
+}
+
+#[derive(Error, Debug)]
+pub enum ParseError {
+    #[error("Parsing error: {0}")]
+    ParseError(#[from] peekable_string::ParseError),
+}
+
+fn parse_line(line: String) -> Result<Problem, ParseError> {
+    Ok(parse_all(&line, |input| {
+        let target = input.parse_i64()?;
+        input.expect_str(": ")?;
+        let inputs = separated_list(input, PeekableString::parse_i64, |input| {
+            input.expect_str(" ")
+        })?;
+        Ok(Problem { target, inputs })
+    })?)
+}
+
+/// All the values the operators can make out of `input`, ignoring any partial result that
+/// already overshoots `target`. Built up one prefix of `input` at a time.
+fn try_combinations(input: &[i64], target: i64, with_concatenation: bool) -> HashSet<i64> {
+    let mut table = DpTable::dense(&[input.len()]);
+    table.fill(|table, state| {
+        let last_num = input[state[0]];
+        if state[0] == 0 {
+            return HashSet::from([last_num]);
+        }
+        let rest_possible_values = table[[state[0] - 1]].iter().filter(|val| **val <= target);
+        let set_with_additions = rest_possible_values.clone().map(|num| num + last_num);
+        let set_with_multiplication = rest_possible_values.clone().map(|num| num * last_num);
+
+        // concatenation is only allowed in part 2
+        let set_with_concatenation = rest_possible_values
+            .filter(|_| with_concatenation)
+            .map(|num| num.to_string() + last_num.to_string().as_str())
+            .map(|str| str.parse::<i64>().unwrap());
+
+        set_with_additions
+            .chain(set_with_multiplication)
+            .chain(set_with_concatenation)
+            .collect()
+    });
+    table[[input.len() - 1]].clone()
+}
@@ -1,73 +1,8 @@
-use std::collections::HashSet;
+use std::process::ExitCode;
 
-use libutils::{memoizer::Memoizer, read_file_map_lines};
+use day7::Day7;
+use libutils::solver::run_main;
 
-fn main() {
-    let problems: Vec<Problem> =
-        read_file_map_lines("input.txt", &mut |line: String| parse_line(line)).unwrap();
-
-    let mut part1_calibration_value = 0;
-    for problem in problems.iter() {
-        let possible_values = try_combinations(&problem.inputs.as_slice(), problem.target);
-        /*println!(
-            "With problem {:?} i have target {:?} and got {:?} possible results",
-            problem.inputs,
-            problem.target,
-            possible_values.len()
-        );*/
-        if possible_values.contains(&problem.target) {
-            //println!("Ok!");
-            part1_calibration_value += problem.target
-        }
-    }
-
-    println!("Part 1/2 calibration value: {:?}", part1_calibration_value)
-}
-
-struct Problem {
-    target: i64,
-    inputs: Vec<i64>,
-}
-
-fn parse_line(line: String) -> Result<Problem, ()> {
-    let mut split_line = line.split(':').into_iter();
-    let target = split_line.next().ok_or(())?.parse().map_err(|_| ())?;
-    let inputs = split_line.next().ok_or(())?;
-    let inputs = inputs
-        .split(' ')
-        .into_iter()
-        .skip(1)
-        .map(|str| str.parse().unwrap())
-        .collect();
-
-    Ok(Problem { target, inputs })
-}
-
-fn try_combinations<'a>(
-    input: &&'a [i64],
-    target: i64,
-) -> HashSet<i64> {
-    let last_num = input[input.len() - 1];
-    let rest = &input[..input.len() - 1];
-    if rest.len() == 0 {
-        let mut base_case = HashSet::new();
-        base_case.insert(last_num);
-        return base_case;
-    }
-    let mut rest_possible_values = try_combinations(&rest, target);
-    rest_possible_values.retain(|val| *val<=target);
-    let set_with_additions = rest_possible_values.iter().map(|num| num + last_num);
-    let set_with_multiplication = rest_possible_values.iter().map(|num| num * last_num);
-
-    // Comment out the set_with_concatenation for part 1
-    let set_with_concatenation = rest_possible_values
-        .iter()
-        .map(|num| num.to_string() + last_num.to_string().as_str())
-        .map(|str| str.parse().unwrap());
-
-    return set_with_additions
-        .chain(set_with_multiplication)
-        // Comment out set_with_concatenation for part 1
-        .chain(set_with_concatenation)
-        .collect();
+fn main() -> ExitCode {
+    run_main(Day7)
 }
@@ -0,0 +1,177 @@
+use std::path::Path;
+
+use crate::input::InputSource;
+use crate::solver::DynSolver;
+
+/// Name of the per-day file listing the known answers for each input file.
+pub const ANSWERS_FILE: &str = "answers.toml";
+/// The full puzzle input, whose answers are only checked by the ignored test since solving it
+/// unoptimized is slow.
+pub const PUZZLE_INPUT: &str = "input.txt";
+
+/// Known answers for one input file. A missing part is not checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedAnswers {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// Parses the small subset of TOML used by `answers.toml`:
+///
+/// ```toml
+/// ["example.txt"]
+/// part1 = 11
+/// part2 = "31"
+/// ```
+pub fn parse_answers(text: &str) -> Result<Vec<ExpectedAnswers>, String> {
+    let mut result: Vec<ExpectedAnswers> = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let line_number = index + 1;
+
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            result.push(ExpectedAnswers {
+                input: unquote(header.trim()).to_string(),
+                part1: None,
+                part2: None,
+            });
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected `key = value`", line_number))?;
+        let section = result
+            .last_mut()
+            .ok_or_else(|| format!("line {}: answer outside of an [input] section", line_number))?;
+        let value = Some(unquote(value.trim()).to_string());
+        match key.trim() {
+            "part1" => section.part1 = value,
+            "part2" => section.part2 = value,
+            other => return Err(format!("line {}: unknown key '{}'", line_number, other)),
+        }
+    }
+    Ok(result)
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Runs `solver` on the inputs registered in `<day_dir>/answers.toml` that `filter` accepts
+/// and returns a description of every answer that does not match.
+pub fn check_answers<F>(
+    solver: &dyn DynSolver,
+    day_dir: &Path,
+    filter: F,
+) -> Result<Vec<String>, String>
+where
+    F: Fn(&ExpectedAnswers) -> bool,
+{
+    let answers_path = day_dir.join(ANSWERS_FILE);
+    let text = std::fs::read_to_string(&answers_path)
+        .map_err(|err| format!("{}: {}", answers_path.display(), err))?;
+    let expected_answers =
+        parse_answers(&text).map_err(|err| format!("{}: {}", answers_path.display(), err))?;
+
+    let mut mismatches = vec![];
+    for expected in expected_answers.into_iter().filter(filter) {
+        let input = InputSource::File(day_dir.join(&expected.input));
+        let answers = match solver.solve(&input) {
+            Ok(answers) => answers,
+            Err(err) => {
+                mismatches.push(format!("{}: failed with {}", input, err));
+                continue;
+            }
+        };
+        for (part, expected, actual) in [
+            (1, &expected.part1, &answers.part1),
+            (2, &expected.part2, &answers.part2),
+        ] {
+            if let Some(expected) = expected {
+                if expected != actual {
+                    mismatches.push(format!(
+                        "{}: part {} expected {}, got {}",
+                        input, part, expected, actual
+                    ));
+                }
+            }
+        }
+    }
+    Ok(mismatches)
+}
+
+/// Generates tests checking the solver against the day's `answers.toml`. The
+/// [`PUZZLE_INPUT`] answers are checked by an ignored test, run it with
+/// `cargo test --release -- --ignored`.
+#[macro_export]
+macro_rules! answer_tests {
+    ($solver:expr) => {
+        #[cfg(test)]
+        mod answer_tests {
+            use super::*;
+
+            fn check(puzzle_input: bool) {
+                let day_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+                let mismatches = $crate::answers::check_answers(&$solver, day_dir, |expected| {
+                    (expected.input == $crate::answers::PUZZLE_INPUT) == puzzle_input
+                })
+                .unwrap();
+                assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+            }
+
+            #[test]
+            fn example_answers_match() {
+                check(false);
+            }
+
+            #[test]
+            #[ignore = "solves the full puzzle input, run with --release"]
+            fn puzzle_answers_match() {
+                check(true);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_answers, ExpectedAnswers};
+
+    #[test]
+    pub fn test_parse_answers() {
+        let answers = parse_answers(
+            "# comment\n[\"example.txt\"]\npart1 = 11\npart2 = \"31\"\n\n[input.txt]\npart2 = 5\n",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                ExpectedAnswers {
+                    input: "example.txt".to_string(),
+                    part1: Some("11".to_string()),
+                    part2: Some("31".to_string()),
+                },
+                ExpectedAnswers {
+                    input: "input.txt".to_string(),
+                    part1: None,
+                    part2: Some("5".to_string()),
+                },
+            ],
+            answers
+        );
+    }
+
+    #[test]
+    pub fn test_parse_answers_errors() {
+        assert!(parse_answers("part1 = 1").is_err());
+        assert!(parse_answers("[a]\npart3 = 1").is_err());
+        assert!(parse_answers("[a]\npart1").is_err());
+    }
+}
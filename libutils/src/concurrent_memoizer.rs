@@ -0,0 +1,136 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::memoizer::MemoizerStats;
+
+/// A [`Memoizer`](crate::memoizer::Memoizer) that can be shared between threads.
+///
+/// Results are spread over independently locked shards, and no lock is held while a result
+/// is being calculated, so recursive calculations can't deadlock. Two threads asking for the
+/// same missing input at once may both calculate it; the first one to finish is kept.
+pub struct ConcurrentMemoizer<TInput, TOutput>
+where
+    TInput: Hash + Eq + Clone,
+    TOutput: Clone,
+{
+    shards: Vec<Mutex<HashMap<TInput, TOutput>>>,
+    hasher: RandomState,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<TInput, TOutput> ConcurrentMemoizer<TInput, TOutput>
+where
+    TInput: Hash + Eq + Clone,
+    TOutput: Clone,
+{
+    /// Creates a memoizer with a few shards per available CPU.
+    pub fn new() -> Self {
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+        Self::with_shards(cpus * 4)
+    }
+
+    pub fn with_shards(shard_count: usize) -> Self {
+        assert!(shard_count > 0, "a memoizer needs at least one shard");
+        Self {
+            shards: (0..shard_count)
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+            hasher: RandomState::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn calculate<FnCalc>(&self, calc: FnCalc, input: TInput) -> TOutput
+    where
+        FnCalc: Fn(&Self, &TInput) -> TOutput,
+    {
+        let shard = self.shard(&input);
+        if let Some(output) = shard.lock().unwrap().get(&input) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return output.clone();
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let res = calc(self, &input);
+        shard.lock().unwrap().entry(input).or_insert(res).clone()
+    }
+
+    pub fn reset(&self) {
+        for shard in self.shards.iter() {
+            shard.lock().unwrap().clear();
+        }
+    }
+
+    pub fn stats(&self) -> MemoizerStats {
+        MemoizerStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: 0,
+            size: self.len(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn shard(&self, input: &TInput) -> &Mutex<HashMap<TInput, TOutput>> {
+        let idx = self.hasher.hash_one(input) as usize % self.shards.len();
+        &self.shards[idx]
+    }
+}
+
+impl<TInput, TOutput> Default for ConcurrentMemoizer<TInput, TOutput>
+where
+    TInput: Hash + Eq + Clone,
+    TOutput: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConcurrentMemoizer;
+
+    fn fibonacci(memoizer: &ConcurrentMemoizer<u64, u64>, n: &u64) -> u64 {
+        if *n < 2 {
+            return *n;
+        }
+        memoizer.calculate(fibonacci, n - 1) + memoizer.calculate(fibonacci, n - 2)
+    }
+
+    #[test]
+    pub fn test_shared_between_threads() {
+        let memoizer = ConcurrentMemoizer::with_shards(4);
+        let results: Vec<u64> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (80..88)
+                .map(|n| {
+                    let memoizer = &memoizer;
+                    scope.spawn(move || memoizer.calculate(fibonacci, n))
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(23416728348467685, results[0]);
+        assert_eq!(679891637638612258, results[7]);
+        assert_eq!(88, memoizer.len());
+        assert!(memoizer.stats().hits > 0);
+
+        memoizer.reset();
+        assert!(memoizer.is_empty());
+    }
+}
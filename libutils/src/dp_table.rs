@@ -0,0 +1,255 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Index;
+
+use crate::memoizer::CycleError;
+
+/// A table of results for a bottom-up dynamic programming solution, computed iteratively.
+///
+/// States are points in a grid of the given dimensions. A dense table allocates a slot for
+/// every state and suits [`fill`](DpTable::fill), which computes all of them. A sparse table
+/// only stores what was computed and suits [`solve`](DpTable::solve), which computes just the
+/// states a target needs.
+pub struct DpTable<T> {
+    dimensions: Vec<usize>,
+    storage: Storage<T>,
+}
+
+enum Storage<T> {
+    Dense(Vec<Option<T>>),
+    Sparse(HashMap<Vec<usize>, T>),
+}
+
+impl<T> DpTable<T> {
+    pub fn dense(dimensions: &[usize]) -> Self {
+        let size = dimensions.iter().product();
+        Self {
+            dimensions: dimensions.to_vec(),
+            storage: Storage::Dense((0..size).map(|_| None).collect()),
+        }
+    }
+
+    pub fn sparse(dimensions: &[usize]) -> Self {
+        Self {
+            dimensions: dimensions.to_vec(),
+            storage: Storage::Sparse(HashMap::new()),
+        }
+    }
+
+    pub fn dimensions(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    /// The result for `state`, if it has been computed.
+    pub fn get(&self, state: &[usize]) -> Option<&T> {
+        match &self.storage {
+            Storage::Dense(values) => values[self.index_of(state)].as_ref(),
+            Storage::Sparse(values) => {
+                self.index_of(state);
+                values.get(state)
+            }
+        }
+    }
+
+    /// Number of states computed so far.
+    pub fn len(&self) -> usize {
+        match &self.storage {
+            Storage::Dense(values) => values.iter().filter(|v| v.is_some()).count(),
+            Storage::Sparse(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Computes every state in row-major order (last dimension varying fastest), so
+    /// `transition` can read any state that comes before the one it is computing.
+    pub fn fill<FnTransition>(&mut self, transition: FnTransition)
+    where
+        FnTransition: Fn(&Self, &[usize]) -> T,
+    {
+        let mut state = vec![0; self.dimensions.len()];
+        if self.dimensions.contains(&0) {
+            return;
+        }
+        loop {
+            let value = transition(self, &state);
+            self.set(&state, value);
+
+            // advance to the next state, like an odometer
+            let mut dim = state.len();
+            loop {
+                if dim == 0 {
+                    return;
+                }
+                dim -= 1;
+                state[dim] += 1;
+                if state[dim] < self.dimensions[dim] {
+                    break;
+                }
+                state[dim] = 0;
+            }
+        }
+    }
+
+    /// Computes `target` and the states it transitively depends on, dependencies first.
+    ///
+    /// `dependencies` lists the states `transition` will read for a given state. Fails with
+    /// the chain of states involved if they depend on each other in a cycle.
+    pub fn solve<FnDependencies, FnTransition>(
+        &mut self,
+        target: &[usize],
+        dependencies: FnDependencies,
+        transition: FnTransition,
+    ) -> Result<&T, CycleError<Vec<usize>>>
+    where
+        FnDependencies: Fn(&[usize]) -> Vec<Vec<usize>>,
+        FnTransition: Fn(&Self, &[usize]) -> T,
+    {
+        // (state, whether its dependencies have been pushed already)
+        let mut stack = vec![(target.to_vec(), false)];
+        let mut in_progress = HashSet::new();
+        while let Some((state, expanded)) = stack.pop() {
+            if expanded {
+                let value = transition(self, &state);
+                self.set(&state, value);
+                in_progress.remove(&state);
+                continue;
+            }
+            if self.get(&state).is_some() {
+                continue;
+            }
+
+            let next = dependencies(&state);
+            in_progress.insert(state.clone());
+            stack.push((state, true));
+            for dependency in next {
+                if in_progress.contains(&dependency) {
+                    return Err(cycle_in(&stack, dependency));
+                }
+                if self.get(&dependency).is_none() {
+                    stack.push((dependency, false));
+                }
+            }
+        }
+        Ok(&self[target])
+    }
+
+    fn set(&mut self, state: &[usize], value: T) {
+        let index = self.index_of(state);
+        match &mut self.storage {
+            Storage::Dense(values) => values[index] = Some(value),
+            Storage::Sparse(values) => {
+                values.insert(state.to_vec(), value);
+            }
+        }
+    }
+
+    /// Row-major position of `state`, panicking if it is outside the table.
+    fn index_of(&self, state: &[usize]) -> usize {
+        assert_eq!(
+            self.dimensions.len(),
+            state.len(),
+            "state {:?} doesn't match table dimensions {:?}",
+            state,
+            self.dimensions
+        );
+        state
+            .iter()
+            .zip(self.dimensions.iter())
+            .fold(0, |index, (coordinate, size)| {
+                assert!(
+                    coordinate < size,
+                    "state {:?} is outside table dimensions {:?}",
+                    state,
+                    self.dimensions
+                );
+                index * size + coordinate
+            })
+    }
+}
+
+/// The expanded states still on the stack are the path from the target to the current state.
+fn cycle_in(stack: &[(Vec<usize>, bool)], repeated: Vec<usize>) -> CycleError<Vec<usize>> {
+    let path: Vec<_> = stack
+        .iter()
+        .filter(|(_, expanded)| *expanded)
+        .map(|(state, _)| state.clone())
+        .collect();
+    let start = path.iter().position(|state| *state == repeated).unwrap();
+    let mut chain = path[start..].to_vec();
+    chain.push(repeated);
+    CycleError { chain }
+}
+
+impl<T> Index<&[usize]> for DpTable<T> {
+    type Output = T;
+
+    /// Panics if `state` hasn't been computed yet.
+    fn index(&self, state: &[usize]) -> &T {
+        self.get(state)
+            .unwrap_or_else(|| panic!("state {:?} has not been computed yet", state))
+    }
+}
+
+/// Same as indexing with a slice, e.g. `table[[x, y]]`.
+impl<T, const N: usize> Index<[usize; N]> for DpTable<T> {
+    type Output = T;
+
+    fn index(&self, state: [usize; N]) -> &T {
+        &self[&state[..]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DpTable;
+
+    #[test]
+    pub fn test_fill_dense() {
+        // number of monotonic lattice paths from the top-left corner
+        let mut table = DpTable::dense(&[4, 5]);
+        table.fill(|table, state| match state {
+            [0, _] | [_, 0] => 1u64,
+            [x, y] => table[[x - 1, *y]] + table[[*x, y - 1]],
+            _ => unreachable!(),
+        });
+        assert_eq!(35, table[[3, 4]]);
+        assert_eq!(20, table.len());
+    }
+
+    #[test]
+    pub fn test_solve_sparse() {
+        // collatz steps, for a state space far too big to fill
+        let next = |n: usize| {
+            if n.is_multiple_of(2) {
+                n / 2
+            } else {
+                3 * n + 1
+            }
+        };
+        let mut table = DpTable::sparse(&[1_000_000]);
+        let steps = table.solve(
+            &[27],
+            |state| match state[0] {
+                1 => vec![],
+                n => vec![vec![next(n)]],
+            },
+            |table, state| match state[0] {
+                1 => 0u32,
+                n => table[[next(n)]] + 1,
+            },
+        );
+        assert_eq!(Ok(&111), steps);
+        assert_eq!(112, table.len());
+    }
+
+    #[test]
+    pub fn test_solve_detects_cycles() {
+        let mut table = DpTable::<u32>::sparse(&[4]);
+        let err = table
+            .solve(&[0], |state| vec![vec![(state[0] + 1) % 3]], |_, _| 0)
+            .unwrap_err();
+        assert_eq!(vec![vec![0], vec![1], vec![2], vec![0]], err.chain);
+    }
+}
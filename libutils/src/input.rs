@@ -0,0 +1,107 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor};
+use std::path::PathBuf;
+
+/// Environment variable consulted by [`InputSource::resolve`] when no input is given on the command line.
+pub const INPUT_ENV_VAR: &str = "AOC_INPUT";
+
+/// Where the puzzle input comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+    Text(String),
+}
+
+impl InputSource {
+    /// Interprets a command line argument: `-` is stdin, anything else is a path.
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::File(PathBuf::from(arg))
+        }
+    }
+
+    pub fn from_env() -> Option<Self> {
+        std::env::var(INPUT_ENV_VAR)
+            .ok()
+            .map(|value| InputSource::from_arg(&value))
+    }
+
+    pub fn from_text(text: &str) -> Self {
+        InputSource::Text(text.to_string())
+    }
+
+    /// Picks the command line argument if present, then the environment variable, then `default`.
+    pub fn resolve(arg: Option<&str>, default: &str) -> Self {
+        match arg {
+            Some(arg) => InputSource::from_arg(arg),
+            None => InputSource::from_env().unwrap_or_else(|| InputSource::from_arg(default)),
+        }
+    }
+
+    pub fn open(&self) -> Result<Box<dyn BufRead>, std::io::Error> {
+        match self {
+            InputSource::File(path) => Ok(Box::new(BufReader::new(File::open(path)?))),
+            InputSource::Stdin => Ok(Box::new(std::io::stdin().lock())),
+            InputSource::Text(text) => Ok(Box::new(Cursor::new(text.clone().into_bytes()))),
+        }
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "<stdin>"),
+            InputSource::Text(_) => write!(f, "<text>"),
+        }
+    }
+}
+
+impl From<&str> for InputSource {
+    fn from(value: &str) -> Self {
+        InputSource::from_arg(value)
+    }
+}
+
+impl From<&String> for InputSource {
+    fn from(value: &String) -> Self {
+        InputSource::from_arg(value.as_str())
+    }
+}
+
+impl From<&InputSource> for InputSource {
+    fn from(value: &InputSource) -> Self {
+        value.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputSource;
+    use crate::{read_file_map_lines, read_file_to_str};
+
+    #[test]
+    pub fn test_from_arg() {
+        assert_eq!(InputSource::Stdin, InputSource::from_arg("-"));
+        assert_eq!(
+            InputSource::File("example.txt".into()),
+            InputSource::from_arg("example.txt")
+        );
+        assert_eq!(
+            InputSource::File("input.txt".into()),
+            InputSource::resolve(Some("input.txt"), "example.txt")
+        );
+    }
+
+    #[test]
+    pub fn test_text_source() {
+        let source = InputSource::from_text("1 2\n3 4\n");
+        let lines = read_file_map_lines(&source, &mut |line: String| Ok::<_, ()>(line.len()));
+        assert_eq!(vec![3, 3], lines.unwrap());
+        assert_eq!("1 2\n3 4\n", read_file_to_str(&source).unwrap());
+    }
+}
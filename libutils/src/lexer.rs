@@ -0,0 +1,204 @@
+use crate::peekable_string::{PeekableString, Position};
+
+/// A configurable tokenizer: declare which tokens exist, and everything else is skipped as noise.
+///
+/// At each position the longest keyword or punctuation match wins, unless an identifier
+/// (when enabled) would be longer, so `done` is an identifier rather than `do` + `ne`.
+#[derive(Debug, Clone, Default)]
+pub struct Lexer {
+    keywords: Vec<&'static str>,
+    punctuation: Vec<&'static str>,
+    identifiers: bool,
+    integers: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind<'a> {
+    Keyword(&'static str),
+    Punctuation(&'static str),
+    Identifier(&'a str),
+    Integer(u64),
+}
+
+/// Where a token is in the input: `start` is inclusive, `end` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind<'a>,
+    pub text: &'a str,
+    pub span: Span,
+}
+
+impl<'a> Token<'a> {
+    /// True if nothing (not even skipped noise) separates `previous` from this token.
+    pub fn follows(&self, previous: &Token) -> bool {
+        previous.span.end.offset == self.span.start.offset
+    }
+}
+
+impl Lexer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn keywords(mut self, keywords: &[&'static str]) -> Self {
+        self.keywords.extend_from_slice(keywords);
+        self
+    }
+
+    pub fn punctuation(mut self, punctuation: &[&'static str]) -> Self {
+        self.punctuation.extend_from_slice(punctuation);
+        self
+    }
+
+    /// Recognizes identifiers: a letter or `_`, followed by letters, digits or `_`.
+    pub fn identifiers(mut self) -> Self {
+        self.identifiers = true;
+        self
+    }
+
+    /// Recognizes unsigned decimal integers.
+    pub fn integers(mut self) -> Self {
+        self.integers = true;
+        self
+    }
+
+    pub fn tokenize<'l, 'a>(&'l self, text: &'a str) -> Tokens<'l, 'a> {
+        Tokens {
+            lexer: self,
+            text,
+            input: PeekableString::new(text),
+        }
+    }
+
+    fn longest_literal(&self, input: &PeekableString) -> Option<TokenKind<'static>> {
+        let keywords = self.keywords.iter().map(|k| TokenKind::Keyword(k));
+        let punctuation = self.punctuation.iter().map(|p| TokenKind::Punctuation(p));
+        keywords
+            .chain(punctuation)
+            .filter(|kind| input.starts_with(literal_text(kind)))
+            .max_by_key(|kind| literal_text(kind).len())
+    }
+}
+
+fn literal_text(kind: &TokenKind<'static>) -> &'static str {
+    match kind {
+        TokenKind::Keyword(text) | TokenKind::Punctuation(text) => text,
+        _ => "",
+    }
+}
+
+fn is_identifier_start(ch: char) -> bool {
+    ch.is_alphabetic() || ch == '_'
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+pub struct Tokens<'l, 'a> {
+    lexer: &'l Lexer,
+    text: &'a str,
+    input: PeekableString<'a>,
+}
+
+impl<'l, 'a> Tokens<'l, 'a> {
+    /// Tries to read a token at the current position, consuming nothing if there is none.
+    fn next_token(&mut self) -> Option<TokenKind<'a>> {
+        let literal = self.lexer.longest_literal(&self.input);
+        let literal_len = literal.as_ref().map_or(0, |kind| literal_text(kind).len());
+
+        let start = self.input.mark();
+        let start_offset = self.input.position().offset;
+        if self.lexer.identifiers && self.input.peek().is_some_and(is_identifier_start) {
+            let identifier_len = self.input.take_while(is_identifier_char).len();
+            if identifier_len > literal_len {
+                let end_offset = self.input.position().offset;
+                return Some(TokenKind::Identifier(&self.text[start_offset..end_offset]));
+            }
+            self.input.reset(start);
+        }
+
+        if let Some(kind) = literal {
+            self.input.expect_str(literal_text(&kind)).ok()?;
+            return Some(kind);
+        }
+
+        if self.lexer.integers {
+            if let Ok(value) = self.input.parse_u64() {
+                return Some(TokenKind::Integer(value));
+            }
+        }
+        None
+    }
+}
+
+impl<'l, 'a> Iterator for Tokens<'l, 'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.input.is_empty() {
+            let start = self.input.position();
+            match self.next_token() {
+                Some(kind) => {
+                    let end = self.input.position();
+                    return Some(Token {
+                        kind,
+                        text: &self.text[start.offset..end.offset],
+                        span: Span { start, end },
+                    });
+                }
+                // noise
+                None => {
+                    self.input.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lexer, TokenKind};
+
+    #[test]
+    pub fn test_tokens_and_noise() {
+        let lexer = Lexer::new()
+            .keywords(&["do", "don't"])
+            .punctuation(&["(", ")", ","])
+            .integers();
+        let tokens: Vec<_> = lexer.tokenize("x don't(12 ,3)").collect();
+        let kinds: Vec<_> = tokens.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            vec![
+                TokenKind::Keyword("don't"),
+                TokenKind::Punctuation("("),
+                TokenKind::Integer(12),
+                TokenKind::Punctuation(","),
+                TokenKind::Integer(3),
+                TokenKind::Punctuation(")"),
+            ],
+            kinds
+        );
+        assert_eq!("12", tokens[2].text);
+        assert_eq!(9, tokens[2].span.start.column);
+        assert!(tokens[1].follows(&tokens[0]));
+        assert!(!tokens[3].follows(&tokens[2]));
+    }
+
+    #[test]
+    pub fn test_identifiers_win_over_shorter_keywords() {
+        let lexer = Lexer::new().keywords(&["do"]).identifiers();
+        let kinds: Vec<_> = lexer.tokenize("do done").map(|t| t.kind).collect();
+        assert_eq!(
+            vec![TokenKind::Keyword("do"), TokenKind::Identifier("done")],
+            kinds
+        );
+    }
+}
@@ -1,28 +1,72 @@
 use std::collections::HashMap;
-use std::fs::File;
 use std::hash::Hash;
-use std::io::BufRead;
-use std::io::BufReader;
 use std::io::Read;
-use std::path::Path;
 
+use input::InputSource;
+use lines::lines_of;
+
+// Lets code generated by `libutils_macros` refer to `::libutils` from inside this crate too.
+extern crate self as libutils;
+
+pub mod answers;
+pub mod concurrent_memoizer;
+pub mod dp_table;
+pub mod input;
+pub mod lexer;
+pub mod lines;
+pub mod log;
 pub mod map2d;
-pub mod peekable_string;
 pub mod memoizer;
+pub mod parser;
+pub mod pathfinding;
+pub mod peekable_string;
+pub mod persist;
+pub mod point;
+pub mod sections;
+pub mod solver;
 
-pub fn read_file_to_str(fname: &str) -> Result<String, std::io::Error> {
-    println!("Loading {0}", fname);
-    let mut file: File = File::open(Path::new(fname))?;
+pub fn read_file_to_str<S>(source: S) -> Result<String, std::io::Error>
+where
+    S: Into<InputSource>,
+{
+    let source = source.into();
+    crate::info!("Loading {0}", source);
     let mut s = String::new();
-    let size = file.read_to_string(&mut s)?;
-    println!("Read {} bytes", size);
-    return Ok(s);
+    let size = source.open()?.read_to_string(&mut s)?;
+    crate::debug!("Read {} bytes", size);
+    Ok(s)
 }
 
 #[derive(Debug)]
 pub enum FileProcessingErr<E> {
     IoError(std::io::Error),
-    ProcessingError(E),
+    /// The line processing function failed. `line_number` is 1-based, and so is `column`, the
+    /// position of the failure in `line` when the caller knows it.
+    ProcessingError {
+        file: String,
+        line_number: usize,
+        line: String,
+        column: Option<usize>,
+        error: E,
+    },
+    /// The input did not have the expected number of blank-line-separated sections.
+    SectionCountError {
+        file: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl<E> FileProcessingErr<E> {
+    fn processing(file: &str, line_index: usize, line: String, error: E) -> Self {
+        FileProcessingErr::ProcessingError {
+            file: file.to_string(),
+            line_number: line_index + 1,
+            line,
+            column: None,
+            error,
+        }
+    }
 }
 
 impl<E> std::convert::From<std::io::Error> for FileProcessingErr<E> {
@@ -31,39 +75,107 @@ impl<E> std::convert::From<std::io::Error> for FileProcessingErr<E> {
     }
 }
 
-pub fn read_file_foreach_line<F, E>(fname: &str, action: &mut F) -> Result<(), FileProcessingErr<E>>
+/// The [`ParseError`](peekable_string::ParseError) behind `error`, if any.
+fn find_parse_error<'e>(
+    error: &'e (dyn std::error::Error + 'static),
+) -> Option<&'e peekable_string::ParseError> {
+    let mut cause = Some(error);
+    while let Some(err) = cause {
+        if let Some(parse_error) = err.downcast_ref() {
+            return Some(parse_error);
+        }
+        cause = err.source();
+    }
+    None
+}
+
+/// Errors whose column is known, given or from a parse error, show the line with a caret under
+/// the failure point. Other errors quote it. Parse errors are reported by the file's line number
+/// rather than their own, which counts from the start of `line`.
+impl<E> std::fmt::Display for FileProcessingErr<E>
 where
-    F: FnMut(String) -> Result<(), E>,
+    E: std::error::Error + 'static,
 {
-    let f = File::open(fname)?;
-    let file = BufReader::new(&f);
-    for line in file.lines() {
-        let l = line.unwrap();
-        if let Err(res) = action(l) {
-            return Err(FileProcessingErr::ProcessingError::<E>(res));
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileProcessingErr::IoError(err) => write!(f, "I/O error: {}", err),
+            FileProcessingErr::ProcessingError {
+                file,
+                line_number,
+                line,
+                column,
+                error,
+            } => {
+                if let Some(parse_error) = find_parse_error(error) {
+                    write!(
+                        f,
+                        "{}:{}:{}: {}\n{}",
+                        file,
+                        line_number,
+                        parse_error.position.column,
+                        parse_error.summary(),
+                        parse_error.snippet(*line_number, line)
+                    )
+                } else if let Some(column) = column {
+                    write!(
+                        f,
+                        "{}:{}:{}: {}\n{}",
+                        file,
+                        line_number,
+                        column,
+                        error,
+                        peekable_string::snippet(*line_number, line, *column)
+                    )
+                } else {
+                    write!(f, "{}:{}: {} (line: {:?})", file, line_number, error, line)
+                }
+            }
+            FileProcessingErr::SectionCountError {
+                file,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{}: expected {} sections separated by blank lines, found {}",
+                file, expected, found
+            ),
         }
     }
-    Ok(())
 }
 
-pub fn read_file_map_lines<F, TRes, E>(
-    fname: &str,
+impl<E> std::error::Error for FileProcessingErr<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FileProcessingErr::IoError(err) => Some(err),
+            FileProcessingErr::ProcessingError { error, .. } => Some(error),
+            FileProcessingErr::SectionCountError { .. } => None,
+        }
+    }
+}
+
+pub fn read_file_foreach_line<S, F, E>(
+    source: S,
+    action: &mut F,
+) -> Result<(), FileProcessingErr<E>>
+where
+    S: Into<InputSource>,
+    F: FnMut(String) -> Result<(), E>,
+{
+    lines_of(source).map_parse(action).collect()
+}
+
+pub fn read_file_map_lines<S, F, TRes, E>(
+    source: S,
     action: &mut F,
 ) -> Result<Vec<TRes>, FileProcessingErr<E>>
 where
+    S: Into<InputSource>,
     F: FnMut(String) -> Result<TRes, E>,
 {
-    let mut result = vec![];
-    let f = File::open(fname)?;
-    let file = BufReader::new(&f);
-    for line in file.lines() {
-        let l = line.unwrap();
-        match action(l) {
-            Err(err) => return Err(FileProcessingErr::ProcessingError::<E>(err)),
-            Ok(val) => result.push(val),
-        }
-    }
-    Ok(result)
+    lines_of(source).map_parse(action).collect()
 }
 
 pub fn minmax_vector<T>(vector: &std::vec::Vec<T>) -> (T, T)
@@ -90,5 +202,57 @@ where
     for item in vector.into_iter() {
         result.entry(item).and_modify(|c| *c += 1).or_insert(1);
     }
-    return result;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::{
+        input::InputSource, parser::parse_all, peekable_string::PeekableString,
+        read_file_map_lines, FileProcessingErr,
+    };
+
+    #[test]
+    pub fn test_processing_error_reports_line() {
+        let source = InputSource::from_text("1\n2\nthree\n4");
+        let err =
+            read_file_map_lines(&source, &mut |line: String| line.parse::<i32>()).unwrap_err();
+        match &err {
+            FileProcessingErr::ProcessingError {
+                line_number, line, ..
+            } => {
+                assert_eq!(3, *line_number);
+                assert_eq!("three", line);
+            }
+            _ => panic!("expected a processing error"),
+        }
+        assert!(err.to_string().starts_with("<text>:3: invalid digit"));
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    pub fn test_parse_error_shows_caret() {
+        let source = InputSource::from_text("1 2\n3 x\n");
+        let err = read_file_map_lines(&source, &mut |line: String| {
+            parse_all(&line, |input: &mut PeekableString| {
+                let a = input.parse_u64()?;
+                input.expect_str(" ")?;
+                Ok((a, input.parse_u64()?))
+            })
+        })
+        .unwrap_err();
+        assert_eq!(
+            "<text>:2:3: expected a number, found 'x'\n  |\n2 | 3 x\n  |   ^",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_io_error_is_propagated() {
+        let source = InputSource::File("does/not/exist.txt".into());
+        let err = read_file_map_lines(&source, &mut |line: String| Ok::<_, ()>(line));
+        assert!(matches!(err, Err(FileProcessingErr::IoError(_))));
+    }
 }
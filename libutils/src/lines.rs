@@ -0,0 +1,133 @@
+use std::io::BufRead;
+
+use crate::input::InputSource;
+use crate::FileProcessingErr;
+
+/// Lazily reads the lines of an input, one at a time.
+///
+/// Failing to open the input is reported as the first item; iteration stops after any I/O error.
+pub fn lines_of<S>(source: S) -> LineReader
+where
+    S: Into<InputSource>,
+{
+    let source = source.into();
+    let (lines, open_error) = match source.open() {
+        Ok(reader) => (Some(reader.lines()), None),
+        Err(err) => (None, Some(err)),
+    };
+    LineReader {
+        source_name: source.to_string(),
+        lines,
+        open_error,
+        line_index: 0,
+    }
+}
+
+pub struct LineReader {
+    source_name: String,
+    lines: Option<std::io::Lines<Box<dyn BufRead>>>,
+    open_error: Option<std::io::Error>,
+    line_index: usize,
+}
+
+impl LineReader {
+    pub fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    /// Parses each line with `parse`, wrapping failures with the position of the offending line.
+    pub fn map_parse<F, TRes, E>(self, parse: F) -> MapParse<F>
+    where
+        F: FnMut(String) -> Result<TRes, E>,
+    {
+        MapParse { lines: self, parse }
+    }
+
+    fn next_numbered(&mut self) -> Option<Result<(usize, String), std::io::Error>> {
+        if let Some(err) = self.open_error.take() {
+            return Some(Err(err));
+        }
+        match self.lines.as_mut()?.next()? {
+            Ok(line) => {
+                let index = self.line_index;
+                self.line_index += 1;
+                Some(Ok((index, line)))
+            }
+            Err(err) => {
+                self.lines = None;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl Iterator for LineReader {
+    type Item = Result<String, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_numbered().map(|line| line.map(|(_, line)| line))
+    }
+}
+
+pub struct MapParse<F> {
+    lines: LineReader,
+    parse: F,
+}
+
+impl<F, TRes, E> Iterator for MapParse<F>
+where
+    F: FnMut(String) -> Result<TRes, E>,
+{
+    type Item = Result<TRes, FileProcessingErr<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, line) = match self.lines.next_numbered()? {
+            Ok(numbered) => numbered,
+            Err(err) => return Some(Err(err.into())),
+        };
+        Some((self.parse)(line.clone()).map_err(|err| {
+            FileProcessingErr::processing(&self.lines.source_name, index, line, err)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lines_of;
+    use crate::input::InputSource;
+    use crate::FileProcessingErr;
+
+    #[test]
+    pub fn test_lazy_fold() {
+        let source = InputSource::from_text("1\n2\n3\n4");
+        let sum = lines_of(&source)
+            .map_parse(|line| line.parse::<i32>())
+            .try_fold(0, |acc, value| value.map(|v| acc + v));
+        assert_eq!(10, sum.unwrap());
+    }
+
+    #[test]
+    pub fn test_stops_at_first_error() {
+        let source = InputSource::from_text("1\nx\n3");
+        let mut parsed = lines_of(&source).map_parse(|line| line.parse::<i32>());
+        assert_eq!(1, parsed.next().unwrap().unwrap());
+        match parsed.next() {
+            Some(Err(FileProcessingErr::ProcessingError { line_number, .. })) => {
+                assert_eq!(2, line_number)
+            }
+            _ => panic!("expected a processing error on line 2"),
+        }
+        let taken: Vec<i32> = lines_of(&source)
+            .map_parse(|line| line.parse::<i32>())
+            .map_while(Result::ok)
+            .collect();
+        assert_eq!(vec![1], taken);
+    }
+
+    #[test]
+    pub fn test_open_error() {
+        let mut lines = lines_of("does/not/exist.txt");
+        assert!(lines.next().unwrap().is_err());
+        assert!(lines.next().is_none());
+    }
+}
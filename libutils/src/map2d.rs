@@ -1,4 +1,11 @@
-#[derive(Clone)]
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::input::InputSource;
+use crate::point::{Connectivity, Direction, Point};
+use crate::{read_file_to_str, FileProcessingErr};
+
+#[derive(Clone, Debug)]
 pub struct Map2D<T>
 where
     T: Copy,
@@ -8,6 +15,34 @@ where
     size_x: usize,
 }
 
+/// A printable view of a [`Map2D`], made with [`Map2D::render`].
+pub struct Render<'m, T, F>
+where
+    T: Copy,
+{
+    map: &'m Map2D<T>,
+    cell_char: F,
+    overlays: HashMap<Point, char>,
+}
+
+/// How [`Map2D::diff`] lays out the two maps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStyle {
+    /// Both maps next to each other, with `<` after every row that changed.
+    SideBySide,
+    /// The new map only, with changed cells drawn as the given character.
+    Inline(char),
+}
+
+/// A character of a text grid that couldn't be turned into a cell. `row` and `column` are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellError<E> {
+    pub row: usize,
+    pub column: usize,
+    pub ch: char,
+    pub error: E,
+}
+
 impl<T> Map2D<T>
 where
     T: Copy,
@@ -20,30 +55,146 @@ where
         }
     }
 
-    pub fn directions(&self) -> &'static [(i32, i32)] {
-        &[
-            (-1, 1),
-            (0, 1),
-            (1, 1),
-            (-1, 0),
-            /*(0, 0),*/ (1, 0),
-            (-1, -1),
-            (0, -1),
-            (1, -1),
-        ]
+    /// Builds a map from a text grid, one row per line, converting each character with `parse_cell`.
+    ///
+    /// The map is as wide as the longest line; shorter lines are padded with `default`.
+    /// Both `\n` and `\r\n` line endings are accepted, and trailing blank lines are ignored.
+    pub fn from_str<F, E>(text: &str, default: T, mut parse_cell: F) -> Result<Self, CellError<E>>
+    where
+        F: FnMut(char) -> Result<T, E>,
+    {
+        let mut lines: Vec<&str> = text.lines().collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        let size_x = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut map = Map2D::new(default, size_x);
+        for (row, line) in lines.into_iter().enumerate() {
+            for (column, ch) in line.chars().enumerate() {
+                let cell = parse_cell(ch).map_err(|error| CellError {
+                    row: row + 1,
+                    column: column + 1,
+                    ch,
+                    error,
+                })?;
+                map.data.push(cell);
+            }
+            map.data.resize((row + 1) * size_x, default);
+        }
+        Ok(map)
+    }
+
+    /// Like [`from_str`](Self::from_str), reading the grid from `source`. A cell that can't be
+    /// parsed is reported at its row and column of the file.
+    pub fn from_file<S, F, E>(
+        source: S,
+        default: T,
+        parse_cell: F,
+    ) -> Result<Self, FileProcessingErr<E>>
+    where
+        S: Into<InputSource>,
+        F: FnMut(char) -> Result<T, E>,
+    {
+        let source = source.into();
+        let text = read_file_to_str(&source)?;
+        Self::from_str(&text, default, parse_cell).map_err(|error| {
+            FileProcessingErr::ProcessingError {
+                file: source.to_string(),
+                line_number: error.row,
+                line: text.lines().nth(error.row - 1).unwrap_or("").to_string(),
+                column: Some(error.column),
+                error: error.error,
+            }
+        })
+    }
+
+    pub fn directions(&self) -> &'static [Direction] {
+        &Direction::ALL
     }
 
     pub fn size_x(&self) -> usize {
         self.size_x
     }
     pub fn size_y(&self) -> usize {
-        if self.data.len() == 0 {
+        if self.data.is_empty() {
             0
         } else {
             ((self.data.len() - 1) / self.size_x) + 1
         }
     }
 
+    /// True if `at` is inside the map, i.e. not answered with the default value.
+    pub fn contains<P>(&self, at: P) -> bool
+    where
+        P: Into<Point>,
+    {
+        let at = at.into();
+        at.x >= 0 && at.y >= 0 && (at.x as usize) < self.size_x && (at.y as usize) < self.size_y()
+    }
+
+    /// The 4 cells sharing an edge with `at`, clockwise from north. Cells outside the map
+    /// are included with the default value.
+    pub fn neighbors4<P>(&self, at: P) -> impl Iterator<Item = (Point, T)> + '_
+    where
+        P: Into<Point>,
+    {
+        self.neighbors(at.into(), Connectivity::Four)
+    }
+
+    /// Like [`neighbors4`](Self::neighbors4), including the diagonals.
+    pub fn neighbors8<P>(&self, at: P) -> impl Iterator<Item = (Point, T)> + '_
+    where
+        P: Into<Point>,
+    {
+        self.neighbors(at.into(), Connectivity::Eight)
+    }
+
+    /// The neighbors of `at` that are inside the map.
+    pub fn neighbors_in_bounds<P>(
+        &self,
+        at: P,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = (Point, T)> + '_
+    where
+        P: Into<Point>,
+    {
+        self.neighbors(at.into(), connectivity)
+            .filter(|(neighbor, _)| self.contains(*neighbor))
+    }
+
+    fn neighbors(
+        &self,
+        at: Point,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = (Point, T)> + '_ {
+        connectivity.directions().iter().map(move |direction| {
+            let neighbor = at + *direction;
+            (neighbor, self.get(neighbor))
+        })
+    }
+
+    /// The value at `at`, or the default if it's outside the map.
+    pub fn get<P>(&self, at: P) -> T
+    where
+        P: Into<Point>,
+    {
+        let at = at.into();
+        self.get_value(at.x, at.y)
+    }
+
+    pub fn set<P>(&mut self, at: P, value: T)
+    where
+        P: Into<Point>,
+    {
+        let at = at.into();
+        self.set_value(at.x, at.y, value)
+    }
+
     pub fn get_value(&self, x: i32, y: i32) -> T {
         if x < 0 || y < 0 {
             return self.default;
@@ -51,7 +202,7 @@ where
 
         let x = x as usize;
         let y = y as usize;
-        return self.get_value_usize(x, y);
+        self.get_value_usize(x, y)
     }
     pub fn get_value_usize(&self, x: usize, y: usize) -> T {
         if x >= self.size_x {
@@ -95,119 +246,282 @@ where
         }
     }
 
-    pub fn fold<F>(&self, initial: F, fold_func: fn(F, &T, i32, i32) -> F) -> F {
+    pub fn fold<F>(&self, initial: F, fold_func: fn(F, &T, Point) -> F) -> F {
         let mut value = initial;
         for x in 0..self.size_x as i32 {
             for y in 0..self.size_y() as i32 {
-                value = fold_func(value, &self.get_value(x, y), x, y);
+                value = fold_func(value, &self.get_value(x, y), Point::new(x, y));
             }
         }
-        return value;
+        value
     }
 
-    pub fn coordinates_with_filter(&self, filter: fn(&T) -> bool) -> Vec<(i32, i32)> {
+    pub fn coordinates_with_filter(&self, filter: fn(&T) -> bool) -> Vec<Point> {
         let mut result = Vec::new();
         for y in 0..self.size_y() as i32 {
             for x in 0..self.size_x() as i32 {
                 if filter(&self.get_value(x, y)) {
-                    result.push((x, y))
+                    result.push(Point::new(x, y))
                 }
             }
         }
-        return result;
+        result
     }
 
+    /// True if the cells after `from`, going in `direction`, are `letter`.
     #[inline]
     pub fn matches_in_straight_direction(
         &self,
-        x: i32,
-        y: i32,
-        move_x: i32,
-        move_y: i32,
+        from: Point,
+        direction: Direction,
         letter: &[T],
     ) -> bool
     where
         T: Eq,
     {
-        if self.get_value(x + move_x, y + move_y) == letter[0] {
+        let next = from + direction;
+        if self.get(next) == letter[0] {
             if letter.len() == 1 {
-                return true;
+                true
             } else {
-                self.matches_in_straight_direction(
-                    x + move_x,
-                    y + move_y,
-                    move_x,
-                    move_y,
-                    &letter[1..],
-                )
+                self.matches_in_straight_direction(next, direction, &letter[1..])
             }
         } else {
-            return false;
+            false
         }
     }
 
+    /// Labels the connected regions of cells passing `filter`, returning the label map and the
+    /// number of regions. Labels go from 0 in row-major order of each region's first cell;
+    /// filtered-out cells get -1. Neighbors are in the same region if `connected_to` holds
+    /// for them in either order.
     pub fn regions_with_filter(
         &self,
         filter: fn(&T) -> bool,
         connected_to: fn(&T, &T) -> bool,
-    ) -> Map2D<i32>
-    where
-        T: PartialEq,
-    {
+        connectivity: Connectivity,
+    ) -> (Map2D<i32>, usize) {
+        let mut sets = DisjointSets::new(self.size_x * self.size_y());
+        for y in 0..self.size_y() as i32 {
+            for x in 0..self.size_x as i32 {
+                let value_current = self.get_value(x, y);
+                if !filter(&value_current) {
+                    continue;
+                }
+                for (neighbor, neighbor_value) in self.neighbors_in_bounds((x, y), connectivity) {
+                    if filter(&neighbor_value)
+                        && (connected_to(&value_current, &neighbor_value)
+                            || connected_to(&neighbor_value, &value_current))
+                    {
+                        sets.union(self.index_of(Point::new(x, y)), self.index_of(neighbor));
+                    }
+                }
+            }
+        }
+
         let mut result = Map2D::new(-1, self.size_x);
         result.data.resize(self.size_y() * self.size_x(), -1);
-
-        let mut todo_list = vec![];
-
-        for x in 0..self.size_x as i32 {
-            for y in 0..self.size_y() as i32 {
-                let value_current = self.get_value(x, y);
-                if filter(&value_current) {
-                    todo_list.push((x, y));
+        let mut region_ids = HashMap::new();
+        for y in 0..self.size_y() as i32 {
+            for x in 0..self.size_x as i32 {
+                if !filter(&self.get_value(x, y)) {
+                    continue;
                 }
+                let root = sets.find(self.index_of(Point::new(x, y)));
+                let next_id = region_ids.len() as i32;
+                result.set_value(x, y, *region_ids.entry(root).or_insert(next_id));
             }
         }
+        (result, region_ids.len())
+    }
 
-        let mut next_group_id = 0;
+    fn index_of(&self, at: Point) -> usize {
+        at.x as usize + at.y as usize * self.size_x
+    }
+}
 
-        while let Some((x, y)) = todo_list.pop() {
-            let value_current = self.get_value(x, y);
+/// Union-find over cell indices, with path halving and union by size.
+struct DisjointSets {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
 
-            if !filter(&value_current) || result.get_value(x, y) != -1 {
-                continue;
-            }
+impl DisjointSets {
+    fn new(count: usize) -> Self {
+        DisjointSets {
+            parent: (0..count).collect(),
+            size: vec![1; count],
+        }
+    }
 
-            let directions = [(x, y - 1), (x, y + 1), (x - 1, y), (x + 1, y)];
+    fn find(&mut self, mut item: usize) -> usize {
+        while self.parent[item] != item {
+            self.parent[item] = self.parent[self.parent[item]];
+            item = self.parent[item];
+        }
+        item
+    }
 
-            let mut current_group_id = None;
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+    }
+}
 
-            // n = neighbor
-            for (nx, ny) in directions {
-                let neighbor_value = self.get_value(nx, ny);
-                let neighbor_group = result.get_value(nx, ny);
-                if filter(&neighbor_value) && connected_to(&value_current, &neighbor_value) {
-                    if neighbor_group < 0 {
-                        todo_list.push((nx, ny))
-                    } else {
-                        current_group_id = Some(neighbor_group);
-                    }
+impl<T> Map2D<T>
+where
+    T: Copy,
+{
+    /// Renders the map as text, one line per row, drawing each cell with `cell_char`.
+    pub fn render<F>(&self, cell_char: F) -> Render<'_, T, F>
+    where
+        F: Fn(&T) -> char,
+    {
+        Render {
+            map: self,
+            cell_char,
+            overlays: HashMap::new(),
+        }
+    }
+
+    /// Coordinates of the cells that differ from `other`.
+    pub fn changed_cells(&self, other: &Map2D<T>) -> Vec<Point>
+    where
+        T: PartialEq,
+    {
+        self.assert_same_size(other);
+        let mut result = vec![];
+        for y in 0..self.size_y() as i32 {
+            for x in 0..self.size_x as i32 {
+                if self.get_value(x, y) != other.get_value(x, y) {
+                    result.push(Point::new(x, y));
                 }
             }
+        }
+        result
+    }
 
-            if let Some(group) = current_group_id {
-                result.set_value(x, y, group)
-            } else {
-                result.set_value(x, y, next_group_id);
-                next_group_id += 1;
+    /// Shows how `new` differs from this map. Panics if the maps have different sizes.
+    pub fn diff<F>(&self, new: &Map2D<T>, style: DiffStyle, cell_char: F) -> String
+    where
+        T: PartialEq,
+        F: Fn(&T) -> char,
+    {
+        self.assert_same_size(new);
+        let mut result = String::new();
+        for y in 0..self.size_y() as i32 {
+            let mut old_row = String::new();
+            let mut new_row = String::new();
+            let mut changed = false;
+            for x in 0..self.size_x as i32 {
+                let (old, new) = (self.get_value(x, y), new.get_value(x, y));
+                old_row.push(cell_char(&old));
+                if old == new {
+                    new_row.push(cell_char(&new));
+                    continue;
+                }
+                changed = true;
+                match style {
+                    DiffStyle::SideBySide => new_row.push(cell_char(&new)),
+                    DiffStyle::Inline(marker) => new_row.push(marker),
+                }
+            }
+            match style {
+                DiffStyle::SideBySide => {
+                    let marker = if changed { " <" } else { "" };
+                    result += &format!("{} | {}{}\n", old_row, new_row, marker);
+                }
+                DiffStyle::Inline(_) => result += &format!("{}\n", new_row),
             }
         }
-        return result;
+        result
+    }
+
+    fn assert_same_size(&self, other: &Map2D<T>) {
+        if (self.size_x(), self.size_y()) != (other.size_x(), other.size_y()) {
+            panic!(
+                "Cannot compare a {}x{} map with a {}x{} one!",
+                self.size_x(),
+                self.size_y(),
+                other.size_x(),
+                other.size_y()
+            );
+        }
+    }
+}
+
+impl<'m, T, F> Render<'m, T, F>
+where
+    T: Copy,
+{
+    /// Draws `ch` over the given cells. Later highlights are drawn over earlier ones.
+    pub fn highlight<I, P>(mut self, coordinates: I, ch: char) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<Point>,
+    {
+        self.overlays.extend(
+            coordinates
+                .into_iter()
+                .map(|coordinate| (coordinate.into(), ch)),
+        );
+        self
+    }
+}
+
+impl<'m, T, F> Display for Render<'m, T, F>
+where
+    T: Copy,
+    F: Fn(&T) -> char,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.map.size_y() as i32 {
+            let row: String = (0..self.map.size_x() as i32)
+                .map(|x| match self.overlays.get(&Point::new(x, y)) {
+                    Some(ch) => *ch,
+                    None => (self.cell_char)(&self.map.get_value(x, y)),
+                })
+                .collect();
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E> Display for CellError<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "row {}, column {}: can't parse {:?}: {}",
+            self.row, self.column, self.ch, self.error
+        )
+    }
+}
+
+impl<E> std::error::Error for CellError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Map2D;
+    use super::{CellError, DiffStyle, Map2D};
+    use crate::input::InputSource;
+    use crate::point::{Connectivity, Point};
 
     #[test]
     pub fn test_get_set_values() {
@@ -258,7 +572,9 @@ mod tests {
         t.set_value(2, 2, Some(6));
         t.set_value(1, 2, Some(6));
 
-        let regions = t.regions_with_filter(|x| x.is_some(), |x, y| x == y);
+        let (regions, count) =
+            t.regions_with_filter(|x| x.is_some(), |x, y| x == y, Connectivity::Four);
+        assert_eq!(2, count);
         assert_eq!(regions.get_value(0, 0), regions.get_value(1, 0));
         assert_eq!(regions.get_value(2, 1), regions.get_value(2, 2));
         assert_eq!(regions.get_value(1, 2), regions.get_value(2, 2));
@@ -267,4 +583,92 @@ mod tests {
         assert_eq!(-1, regions.get_value(0, 1));
         assert_eq!(-1, regions.get_value(1, 1));
     }
+
+    #[test]
+    pub fn test_from_str() {
+        let parse_digit = |ch: char| ch.to_digit(10).ok_or("not a digit");
+        let t = Map2D::from_str("123\r\n4\r\n56\n\n", 0, parse_digit).unwrap();
+        assert_eq!((3, 3), (t.size_x(), t.size_y()));
+        assert_eq!(3, t.get_value(2, 0));
+        assert_eq!(0, t.get_value(1, 1));
+        assert_eq!(6, t.get_value(1, 2));
+
+        let err = Map2D::from_str("12\n3x", 0, parse_digit).unwrap_err();
+        assert_eq!(
+            CellError {
+                row: 2,
+                column: 2,
+                ch: 'x',
+                error: "not a digit"
+            },
+            err
+        );
+    }
+
+    #[test]
+    pub fn test_from_file_shows_caret() {
+        let source = InputSource::from_text("12\n3x\n");
+        let err = Map2D::from_file(&source, 0, |ch| ch.to_string().parse::<u32>()).unwrap_err();
+        assert_eq!(
+            "<text>:2:2: invalid digit found in string\n  |\n2 | 3x\n  |  ^",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_render_and_diff() {
+        let parse = |ch: char| Ok::<_, ()>(ch == '#');
+        let draw = |cell: &bool| if *cell { '#' } else { '.' };
+        let old = Map2D::from_str("#..\n...\n", false, parse).unwrap();
+        let new = Map2D::from_str("#..\n.#.\n", false, parse).unwrap();
+
+        assert_eq!("#..\n...\n", old.render(draw).to_string());
+        let highlighted = old.render(draw).highlight([(1, 0), (2, 0)], 'o');
+        assert_eq!("#oo\n...\n", highlighted.to_string());
+
+        assert_eq!(vec![Point::new(1, 1)], old.changed_cells(&new));
+        assert_eq!(
+            "#.. | #..\n... | .#. <\n",
+            old.diff(&new, DiffStyle::SideBySide, draw)
+        );
+        assert_eq!("#..\n.*.\n", old.diff(&new, DiffStyle::Inline('*'), draw));
+    }
+
+    #[test]
+    pub fn test_neighbors() {
+        let t = Map2D::from_str("12\n34\n", 0, |ch| ch.to_digit(10).ok_or(())).unwrap();
+        let values: Vec<_> = t.neighbors4((0, 0)).map(|(_, value)| value).collect();
+        assert_eq!(vec![0, 2, 3, 0], values);
+        assert_eq!(8, t.neighbors8((0, 0)).count());
+
+        let in_bounds: Vec<_> = t
+            .neighbors_in_bounds(Point::new(1, 1), Connectivity::Eight)
+            .collect();
+        assert_eq!(
+            vec![
+                (Point::new(1, 0), 2),
+                (Point::new(0, 1), 3),
+                (Point::new(0, 0), 1)
+            ],
+            in_bounds
+        );
+        assert!(!t.contains((2, 0)));
+    }
+
+    #[test]
+    pub fn test_regions_are_not_split() {
+        // a U shape, whose arms only meet at the bottom
+        let t = Map2D::from_str("#.#\n#.#\n###\n", false, |ch| Ok::<_, ()>(ch == '#')).unwrap();
+        let (regions, count) = t.regions_with_filter(|c| *c, |_, _| true, Connectivity::Four);
+        assert_eq!(1, count);
+        assert_eq!(0, regions.get_value(0, 0));
+        assert_eq!(0, regions.get_value(2, 0));
+
+        let t = Map2D::from_str("#.\n.#\n", false, |ch| Ok::<_, ()>(ch == '#')).unwrap();
+        let (_, count) = t.regions_with_filter(|c| *c, |_, _| true, Connectivity::Four);
+        assert_eq!(2, count);
+        let (regions, count) = t.regions_with_filter(|c| *c, |_, _| true, Connectivity::Eight);
+        assert_eq!(1, count);
+        assert_eq!(regions.get_value(0, 0), regions.get_value(1, 1));
+    }
 }
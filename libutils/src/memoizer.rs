@@ -1,12 +1,40 @@
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::fmt::{Debug, Display};
 use std::hash::Hash;
 
+pub use libutils_macros::memoize;
 
 pub struct Memoizer<TInput, TOutput>
 where
     TInput: Hash + Eq + Clone,
 {
-    memoized_results: HashMap<TInput, TOutput>,
+    /// Each result is stored along with the tick it was last used at.
+    memoized_results: HashMap<TInput, (TOutput, u64)>,
+    /// Inputs ordered by last use, oldest first. Only maintained when there is a capacity.
+    usage_order: BTreeMap<u64, TInput>,
+    capacity: Option<usize>,
+    tick: u64,
+    stats: MemoizerStats,
+    /// Inputs whose calculation has started but not finished, with their recursion depth.
+    in_progress: HashMap<TInput, usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoizerStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    /// Number of results currently cached.
+    pub size: usize,
+}
+
+/// Returned when calculating an input ends up asking for that same input again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<TInput> {
+    /// The inputs being calculated, from the first one in the cycle back to itself.
+    pub chain: Vec<TInput>,
 }
 
 impl<TInput, TOutput> Memoizer<TInput, TOutput>
@@ -16,21 +44,422 @@ where
     pub fn new() -> Self {
         Self {
             memoized_results: HashMap::new(),
+            usage_order: BTreeMap::new(),
+            capacity: None,
+            tick: 0,
+            stats: MemoizerStats::default(),
+            in_progress: HashMap::new(),
+        }
+    }
+
+    /// Creates a memoizer that keeps at most `capacity` results, evicting the least recently used.
+    pub fn with_capacity_limit(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "a memoizer needs room for at least one result"
+        );
+        Self {
+            capacity: Some(capacity),
+            ..Self::new()
         }
     }
-    pub fn calculate<FnCalc>(&mut self, calc: FnCalc, input: TInput) -> &TOutput
+
+    /// Returns the result for `input`, running `calc` if it isn't cached yet.
+    ///
+    /// `calc` can recursively ask the memoizer for other inputs. If one of those is an input
+    /// that is still being calculated, that inner call fails with a [`CycleError`] instead.
+    pub fn calculate<FnCalc>(
+        &mut self,
+        calc: FnCalc,
+        input: TInput,
+    ) -> Result<&TOutput, CycleError<TInput>>
+    where
+        FnCalc: FnOnce(&mut Self, &TInput) -> TOutput,
+    {
+        self.calculate_with(|memoizer, input| Ok(calc(memoizer, input)), input)
+    }
+
+    /// Like [`calculate`](Self::calculate), for calculations that can fail. Errors are returned
+    /// but not cached, so asking for the same input again retries the calculation.
+    pub fn try_calculate<FnCalc, E>(&mut self, calc: FnCalc, input: TInput) -> Result<&TOutput, E>
+    where
+        FnCalc: FnOnce(&mut Self, &TInput) -> Result<TOutput, E>,
+        E: From<CycleError<TInput>>,
+    {
+        self.calculate_with(calc, input)
+    }
+
+    /// Like [`calculate`](Self::calculate), but takes a borrowed input (e.g. a `&[i64]` for a
+    /// `Vec<i64>` input), which is only copied if the result isn't cached yet.
+    pub fn calculate_borrowed<Q, FnCalc>(
+        &mut self,
+        calc: FnCalc,
+        input: &Q,
+    ) -> Result<&TOutput, CycleError<TInput>>
+    where
+        TInput: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = TInput> + ?Sized,
+        FnCalc: FnOnce(&mut Self, &TInput) -> TOutput,
+    {
+        if self.memoized_results.contains_key(input) {
+            return Ok(self.get(input).unwrap());
+        }
+        self.calculate(calc, input.to_owned())
+    }
+
+    /// Looks up a cached result without calculating anything. Counts as a hit if found.
+    pub fn get<Q>(&mut self, input: &Q) -> Option<&TOutput>
+    where
+        TInput: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !self.memoized_results.contains_key(input) {
+            return None;
+        }
+        self.stats.hits += 1;
+        self.touch(input);
+        self.memoized_results.get(input).map(|(output, _)| output)
+    }
+
+    pub fn contains<Q>(&self, input: &Q) -> bool
+    where
+        TInput: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.memoized_results.contains_key(input)
+    }
+
+    fn calculate_with<FnCalc, E>(&mut self, calc: FnCalc, input: TInput) -> Result<&TOutput, E>
     where
-        FnCalc: Fn(&mut Self, &TInput) -> TOutput,
+        FnCalc: FnOnce(&mut Self, &TInput) -> Result<TOutput, E>,
+        E: From<CycleError<TInput>>,
     {
-        if !self.memoized_results.contains_key(&input) {
-            let res = calc(self, &input);
-            self.memoized_results.insert(input.clone(), res);
+        if self.memoized_results.contains_key(&input) {
+            return Ok(self.get(&input).unwrap());
         }
+        if let Some(&depth) = self.in_progress.get(&input) {
+            return Err(self.cycle_from(depth, input).into());
+        }
+
+        self.stats.misses += 1;
+        let depth = self.in_progress.len();
+        self.in_progress.insert(input.clone(), depth);
+        let res = calc(self, &input);
+        self.in_progress.remove(&input);
+        Ok(self.insert(input, res?))
+    }
 
-        return self.memoized_results.get(&input).unwrap();
+    fn cycle_from(&self, depth: usize, input: TInput) -> CycleError<TInput> {
+        let mut cycle: Vec<_> = self
+            .in_progress
+            .iter()
+            .filter(|(_, d)| **d >= depth)
+            .collect();
+        cycle.sort_by_key(|(_, d)| **d);
+        let mut chain: Vec<_> = cycle.into_iter().map(|(i, _)| i.clone()).collect();
+        chain.push(input);
+        CycleError { chain }
     }
 
     pub fn reset(&mut self) {
         self.memoized_results.clear();
+        self.usage_order.clear();
+    }
+
+    pub fn stats(&self) -> MemoizerStats {
+        MemoizerStats {
+            size: self.len(),
+            ..self.stats
+        }
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = MemoizerStats::default();
+    }
+
+    pub fn len(&self) -> usize {
+        self.memoized_results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.memoized_results.is_empty()
+    }
+
+    /// The cached results, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&TInput, &TOutput)> {
+        self.memoized_results
+            .iter()
+            .map(|(input, (output, _))| (input, output))
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn touch<Q>(&mut self, input: &Q)
+    where
+        TInput: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.capacity.is_none() {
+            return;
+        }
+        let tick = self.next_tick();
+        let entry = self.memoized_results.get_mut(input).unwrap();
+        let key = self.usage_order.remove(&entry.1).unwrap();
+        entry.1 = tick;
+        self.usage_order.insert(tick, key);
+    }
+
+    fn insert(&mut self, input: TInput, output: TOutput) -> &TOutput {
+        let Some(capacity) = self.capacity else {
+            return &self.memoized_results.entry(input).or_insert((output, 0)).0;
+        };
+        let tick = self.next_tick();
+        self.usage_order.insert(tick, input.clone());
+        self.memoized_results.insert(input, (output, tick));
+
+        while self.memoized_results.len() > capacity {
+            let (_, oldest) = self.usage_order.pop_first().unwrap();
+            self.memoized_results.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+        // the newest result is never the one evicted
+        let (_, newest) = self.usage_order.last_key_value().unwrap();
+        &self.memoized_results[newest].0
+    }
+}
+
+/// Adds precomputed results, e.g. loaded from a [`CacheFile`](crate::persist::CacheFile).
+impl<TInput, TOutput> Extend<(TInput, TOutput)> for Memoizer<TInput, TOutput>
+where
+    TInput: Hash + Eq + Clone,
+{
+    fn extend<I: IntoIterator<Item = (TInput, TOutput)>>(&mut self, iter: I) {
+        for (input, output) in iter {
+            if !self.memoized_results.contains_key(&input) {
+                self.insert(input, output);
+            }
+        }
+    }
+}
+
+impl<TInput> Display for CycleError<TInput>
+where
+    TInput: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let chain: Vec<String> = self.chain.iter().map(|i| format!("{:?}", i)).collect();
+        write!(f, "cycle while memoizing: {}", chain.join(" -> "))
+    }
+}
+
+impl<TInput> std::error::Error for CycleError<TInput> where TInput: Debug {}
+
+impl<TInput, TOutput> Default for Memoizer<TInput, TOutput>
+where
+    TInput: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{memoize, CycleError, Memoizer, MemoizerStats};
+
+    fn fibonacci(memoizer: &mut Memoizer<u64, u64>, n: &u64) -> u64 {
+        if *n < 2 {
+            return *n;
+        }
+        *memoizer.calculate(fibonacci, n - 1).unwrap()
+            + *memoizer.calculate(fibonacci, n - 2).unwrap()
+    }
+
+    #[test]
+    pub fn test_stats() {
+        let mut memoizer = Memoizer::new();
+        assert_eq!(Ok(&55), memoizer.calculate(fibonacci, 10));
+        assert_eq!(
+            MemoizerStats {
+                hits: 8,
+                misses: 11,
+                evictions: 0,
+                size: 11,
+            },
+            memoizer.stats()
+        );
+    }
+
+    #[test]
+    pub fn test_lru_eviction() {
+        let mut memoizer = Memoizer::with_capacity_limit(2);
+        let double = |_: &mut Memoizer<u32, u32>, n: &u32| n * 2;
+        memoizer.calculate(double, 1).unwrap();
+        memoizer.calculate(double, 2).unwrap();
+        // 1 becomes the most recently used, so 2 is evicted next
+        memoizer.calculate(double, 1).unwrap();
+        memoizer.calculate(double, 3).unwrap();
+        assert_eq!(2, memoizer.len());
+        assert_eq!(1, memoizer.stats().evictions);
+
+        memoizer.reset_stats();
+        memoizer.calculate(double, 1).unwrap();
+        memoizer.calculate(double, 2).unwrap();
+        assert_eq!(1, memoizer.stats().hits);
+        assert_eq!(1, memoizer.stats().misses);
+    }
+
+    #[test]
+    pub fn test_bounded_recursion() {
+        let mut memoizer = Memoizer::with_capacity_limit(3);
+        assert_eq!(Ok(&6765), memoizer.calculate(fibonacci, 20));
+        assert!(memoizer.len() <= 3);
+    }
+
+    // node -> successors; 1 -> 2 -> 3 -> 1 is a cycle, 4 is a dead end
+    const GRAPH: [&[usize]; 5] = [&[1, 4], &[2], &[3], &[1], &[]];
+
+    fn longest_path(
+        memoizer: &mut Memoizer<usize, Result<u32, CycleError<usize>>>,
+        node: &usize,
+    ) -> Result<u32, CycleError<usize>> {
+        let mut longest = 0;
+        for next in GRAPH[*node].iter() {
+            longest = longest.max(memoizer.calculate(longest_path, *next)?.clone()? + 1);
+        }
+        Ok(longest)
+    }
+
+    #[test]
+    pub fn test_cycle_detection() {
+        let mut memoizer = Memoizer::new();
+        let err = memoizer
+            .calculate(longest_path, 0)
+            .unwrap()
+            .clone()
+            .unwrap_err();
+        assert_eq!(vec![1, 2, 3, 1], err.chain);
+        assert_eq!("cycle while memoizing: 1 -> 2 -> 3 -> 1", err.to_string());
+        assert_eq!(Ok(&Ok(0)), memoizer.calculate(longest_path, 4));
+    }
+
+    // calculations get a reference to the owned input type
+    #[allow(clippy::ptr_arg)]
+    fn sum_halves(memoizer: &mut Memoizer<Vec<i64>, i64>, values: &Vec<i64>) -> i64 {
+        if values.len() < 2 {
+            return values.iter().sum();
+        }
+        let (left, right) = values.split_at(values.len() / 2);
+        *memoizer.calculate_borrowed(sum_halves, left).unwrap()
+            + *memoizer.calculate_borrowed(sum_halves, right).unwrap()
+    }
+
+    #[test]
+    pub fn test_borrowed_lookup() {
+        let mut memoizer = Memoizer::new();
+        assert_eq!(Ok(&10), memoizer.calculate(sum_halves, vec![1, 2, 3, 4]));
+        let values = [5, 1, 2, 3, 4];
+        assert!(memoizer.contains(&values[1..3]));
+        assert_eq!(Some(&7), memoizer.get(&values[3..]));
+        assert_eq!(None, memoizer.get(&values[..2]));
+        assert_eq!(
+            Ok(&15),
+            memoizer.calculate_borrowed(sum_halves, &values[..])
+        );
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum DigitError {
+        Odd(u32),
+        Cycle,
+    }
+
+    impl From<CycleError<u32>> for DigitError {
+        fn from(_: CycleError<u32>) -> Self {
+            DigitError::Cycle
+        }
+    }
+
+    #[test]
+    pub fn test_try_calculate_does_not_cache_errors() {
+        let halve = |_: &mut Memoizer<u32, u32>, n: &u32| {
+            if n.is_multiple_of(2) {
+                Ok(n / 2)
+            } else {
+                Err(DigitError::Odd(*n))
+            }
+        };
+        let mut memoizer = Memoizer::new();
+        assert_eq!(Ok(&2), memoizer.try_calculate(halve, 4));
+        assert_eq!(Err(DigitError::Odd(3)), memoizer.try_calculate(halve, 3));
+        assert_eq!(1, memoizer.len());
+        assert_eq!(Err(DigitError::Odd(3)), memoizer.try_calculate(halve, 3));
+        assert_eq!(3, memoizer.stats().misses);
+
+        let looping = |memoizer: &mut Memoizer<u32, u32>, n: &u32| {
+            memoizer.try_calculate(|_, _| Ok(0), *n).copied()
+        };
+        assert_eq!(Err(DigitError::Cycle), memoizer.try_calculate(looping, 7));
+    }
+
+    #[memoize]
+    fn count_paths(width: u64, height: u64) -> u64 {
+        if width == 0 || height == 0 {
+            return 1;
+        }
+        count_paths(width - 1, height) + count_paths(width, height - 1)
+    }
+
+    #[test]
+    pub fn test_memoize_attribute() {
+        assert_eq!(2, count_paths(1, 1));
+        assert_eq!(137846528820, count_paths(20, 20));
+    }
+
+    #[memoize]
+    fn total(values: Vec<u64>) -> u64 {
+        if values.len() < 2 {
+            return values.into_iter().sum();
+        }
+        let mut values = values;
+        let last = values.pop().unwrap();
+        total(values) + last
+    }
+
+    #[memoize]
+    fn countdown(mut n: u64) -> u64 {
+        if n == 0 {
+            return 0;
+        }
+        n -= 1;
+        countdown(n) + 1
+    }
+
+    #[test]
+    pub fn test_memoize_owned_and_mut_arguments() {
+        assert_eq!(10, total(vec![1, 2, 3, 4]));
+        assert_eq!(50, countdown(50));
+    }
+
+    #[memoize(key = stones.clone(), key_type = Vec<u64>, scope = call)]
+    fn blink_count(stones: Vec<u64>, calls: &std::cell::Cell<u32>) -> usize {
+        calls.set(calls.get() + 1);
+        match stones.split_first() {
+            None => 0,
+            Some((first, rest)) => 1 + blink_count(rest.to_vec(), calls) + *first as usize,
+        }
+    }
+
+    #[test]
+    pub fn test_memoize_key_and_scope() {
+        let calls = std::cell::Cell::new(0);
+        assert_eq!(9, blink_count(vec![1, 2, 3], &calls));
+        assert_eq!(4, calls.get());
+        // a new call starts from an empty cache
+        assert_eq!(9, blink_count(vec![1, 2, 3], &calls));
+        assert_eq!(8, calls.get());
     }
 }
@@ -0,0 +1,110 @@
+use crate::peekable_string::{ParseResult, PeekableString};
+
+/// A parser is anything that consumes a prefix of the input and produces a value.
+pub trait Parser<'a, T>: FnMut(&mut PeekableString<'a>) -> ParseResult<T> {}
+
+impl<'a, T, F> Parser<'a, T> for F where F: FnMut(&mut PeekableString<'a>) -> ParseResult<T> {}
+
+pub fn optional<'a, T, P>(input: &mut PeekableString<'a>, parser: P) -> Option<T>
+where
+    P: Parser<'a, T>,
+{
+    input.try_parse(parser).ok()
+}
+
+/// Tries each parser in order from the same position, returning the first success.
+pub fn alt<'a, T>(
+    input: &mut PeekableString<'a>,
+    parsers: &mut [&mut dyn Parser<'a, T>],
+) -> ParseResult<T> {
+    let mut expected = vec![];
+    for parser in parsers.iter_mut() {
+        match input.try_parse(&mut **parser) {
+            Ok(value) => return Ok(value),
+            Err(err) => expected.push(err.expected),
+        }
+    }
+    input.error(&expected.join(" or "))
+}
+
+/// Parses one or more `item`s separated by `separator`. Once a separator matches, an item
+/// must follow it: its error is returned rather than stopping the list before the separator.
+pub fn separated_list<'a, T, S, P, PSep>(
+    input: &mut PeekableString<'a>,
+    mut item: P,
+    mut separator: PSep,
+) -> ParseResult<Vec<T>>
+where
+    P: Parser<'a, T>,
+    PSep: Parser<'a, S>,
+{
+    let mut result = vec![item(input)?];
+    while optional(input, &mut separator).is_some() {
+        result.push(item(input)?);
+    }
+    Ok(result)
+}
+
+/// Runs `parser` on the whole of `text`, failing if anything is left over.
+pub fn parse_all<'a, T, P>(text: &'a str, mut parser: P) -> ParseResult<T>
+where
+    P: Parser<'a, T>,
+{
+    let mut input = PeekableString::from(text);
+    let result = parser(&mut input)?;
+    input.expect_end()?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{alt, optional, parse_all, separated_list};
+    use crate::peekable_string::PeekableString;
+
+    #[test]
+    pub fn test_separated_list() {
+        let list = parse_all("1,2,3", |input| {
+            separated_list(input, PeekableString::parse_u64, |input| {
+                input.expect_str(",")
+            })
+        });
+        assert_eq!(Ok(vec![1, 2, 3]), list);
+
+        let mut input = PeekableString::from("1,2;");
+        let list = separated_list(&mut input, PeekableString::parse_u64, |input| {
+            input.expect_str(",")
+        });
+        assert_eq!(Ok(vec![1, 2]), list);
+        assert_eq!(Some(';'), input.peek());
+    }
+
+    #[test]
+    pub fn test_separated_list_reports_item_after_separator() {
+        let err = parse_all("61,x3", |input| {
+            separated_list(input, PeekableString::parse_u64, |input| {
+                input.expect_str(",")
+            })
+        })
+        .unwrap_err();
+        assert_eq!("a number", err.expected);
+        assert_eq!(Some('x'), err.found);
+        assert_eq!(4, err.position.column);
+    }
+
+    #[test]
+    pub fn test_optional_and_alt() {
+        let mut input = PeekableString::from("don't()");
+        assert_eq!(None, optional(&mut input, |input| input.expect_str("do()")));
+        let mut parse_do = |input: &mut PeekableString| input.expect_str("do()").map(|_| true);
+        let mut parse_dont =
+            |input: &mut PeekableString| input.expect_str("don't()").map(|_| false);
+        assert_eq!(
+            Ok(false),
+            alt(&mut input, &mut [&mut parse_do, &mut parse_dont])
+        );
+        assert!(input.is_empty());
+
+        let err = alt(&mut input, &mut [&mut parse_do, &mut parse_dont]).unwrap_err();
+        assert_eq!("\"do()\" or \"don't()\"", err.expected);
+    }
+}
@@ -0,0 +1,232 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::map2d::Map2D;
+use crate::point::{Connectivity, Point};
+
+/// What the searches walk through: a position on the map, plus anything else the cost of a
+/// move depends on (e.g. the direction being faced).
+pub trait SearchState: Clone + Eq + Hash {
+    fn position(&self) -> Point;
+}
+
+impl SearchState for Point {
+    fn position(&self) -> Point {
+        *self
+    }
+}
+
+/// A position along with extra state, such as a facing direction.
+impl<E> SearchState for (Point, E)
+where
+    E: Clone + Eq + Hash,
+{
+    fn position(&self) -> Point {
+        self.0
+    }
+}
+
+/// A cheapest way to the goal: its total cost and every state along it, start and goal included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPath<S> {
+    pub distance: u64,
+    pub path: Vec<S>,
+}
+
+/// Shortest path from `start` to `goal` when every move costs 1, only stepping on cells
+/// inside the map for which `passable` is true.
+pub fn bfs<T, FPass>(
+    map: &Map2D<T>,
+    start: Point,
+    goal: Point,
+    connectivity: Connectivity,
+    passable: FPass,
+) -> Option<ShortestPath<Point>>
+where
+    T: Copy,
+    FPass: Fn(&T) -> bool,
+{
+    let mut came_from = HashMap::from([(start, start)]);
+    let mut todo_list = VecDeque::from([start]);
+    while let Some(current) = todo_list.pop_front() {
+        if current == goal {
+            let mut path = vec![current];
+            while *path.last().unwrap() != start {
+                path.push(came_from[path.last().unwrap()]);
+            }
+            path.reverse();
+            return Some(ShortestPath {
+                distance: path.len() as u64 - 1,
+                path,
+            });
+        }
+        for (next, value) in map.neighbors_in_bounds(current, connectivity) {
+            if passable(&value) && !came_from.contains_key(&next) {
+                came_from.insert(next, current);
+                todo_list.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Cheapest path from `start` to any state at `goal`.
+///
+/// `moves` lists the states reachable from a state in one move, with the cost of the move.
+/// Moves onto cells outside the map or not `passable` are ignored.
+pub fn dijkstra<T, S, FPass, FMoves>(
+    map: &Map2D<T>,
+    start: S,
+    goal: Point,
+    passable: FPass,
+    moves: FMoves,
+) -> Option<ShortestPath<S>>
+where
+    T: Copy,
+    S: SearchState,
+    FPass: Fn(&T) -> bool,
+    FMoves: Fn(&S) -> Vec<(S, u64)>,
+{
+    a_star(map, start, goal, passable, moves, |_| 0)
+}
+
+/// Like [`dijkstra`], exploring first the states that `heuristic` estimates closer to `goal`.
+///
+/// The heuristic must never overestimate the remaining cost, otherwise the path found may
+/// not be the cheapest. [`Point::manhattan_distance`] to the goal fits when moves cost at
+/// least 1 per cell and there are no diagonals.
+pub fn a_star<T, S, FPass, FMoves, FHeuristic>(
+    map: &Map2D<T>,
+    start: S,
+    goal: Point,
+    passable: FPass,
+    moves: FMoves,
+    heuristic: FHeuristic,
+) -> Option<ShortestPath<S>>
+where
+    T: Copy,
+    S: SearchState,
+    FPass: Fn(&T) -> bool,
+    FMoves: Fn(&S) -> Vec<(S, u64)>,
+    FHeuristic: Fn(&S) -> u64,
+{
+    // states are numbered in the order they are found, so the heap doesn't need S: Ord
+    let mut states = vec![start.clone()];
+    let mut ids = HashMap::from([(start.clone(), 0)]);
+    let mut best_cost = vec![0];
+    let mut came_from: Vec<Option<usize>> = vec![None];
+    let mut todo_list = BinaryHeap::from([Reverse((heuristic(&start), 0, 0usize))]);
+
+    while let Some(Reverse((_, cost, id))) = todo_list.pop() {
+        if cost > best_cost[id] {
+            continue; // already reached more cheaply
+        }
+        if states[id].position() == goal {
+            let mut path = vec![states[id].clone()];
+            let mut current = id;
+            while let Some(previous) = came_from[current] {
+                path.push(states[previous].clone());
+                current = previous;
+            }
+            path.reverse();
+            return Some(ShortestPath {
+                distance: cost,
+                path,
+            });
+        }
+
+        for (next, move_cost) in moves(&states[id]) {
+            let position = next.position();
+            if !map.contains(position) || !passable(&map.get(position)) {
+                continue;
+            }
+            let next_cost = cost + move_cost;
+            let next_id = *ids.entry(next.clone()).or_insert_with(|| {
+                states.push(next.clone());
+                best_cost.push(u64::MAX);
+                came_from.push(None);
+                states.len() - 1
+            });
+            if next_cost < best_cost[next_id] {
+                best_cost[next_id] = next_cost;
+                came_from[next_id] = Some(id);
+                todo_list.push(Reverse((next_cost + heuristic(&next), next_cost, next_id)));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{a_star, bfs, dijkstra};
+    use crate::map2d::Map2D;
+    use crate::point::{Connectivity, Direction, Point};
+
+    fn maze() -> Map2D<bool> {
+        let text = "\
+...#....
+.#.#.##.
+.#...#..
+.####.#.
+........
+";
+        Map2D::from_str(text, false, |ch| Ok::<_, ()>(ch == '.')).unwrap()
+    }
+
+    #[test]
+    pub fn test_bfs() {
+        let map = maze();
+        let start = Point::new(0, 0);
+        let result = bfs(&map, start, Point::new(7, 0), Connectivity::Four, |c| *c).unwrap();
+        assert_eq!(11, result.distance);
+        assert_eq!(12, result.path.len());
+        assert_eq!(start, result.path[0]);
+        for step in result.path.windows(2) {
+            assert_eq!(1, step[0].manhattan_distance(step[1]));
+            assert!(map.get(step[1]));
+        }
+
+        let blocked = bfs(&map, start, Point::new(3, 0), Connectivity::Four, |c| *c);
+        assert_eq!(None, blocked);
+    }
+
+    #[test]
+    pub fn test_dijkstra_with_facing_direction() {
+        // moving forward costs 1, turning in place costs 1000
+        let map = maze();
+        let moves = |(position, facing): &(Point, Direction)| {
+            vec![
+                ((*position + *facing, *facing), 1),
+                ((*position, facing.turn_right()), 1000),
+                ((*position, facing.turn_left()), 1000),
+            ]
+        };
+        let start = (Point::new(0, 0), Direction::East);
+        let result = dijkstra(&map, start, Point::new(7, 4), |c| *c, moves).unwrap();
+        // down the left edge and along the bottom needs only two turns
+        assert_eq!(2011, result.distance);
+        assert_eq!(14, result.path.len());
+        assert_eq!(
+            Some(&(Point::new(7, 4), Direction::East)),
+            result.path.last()
+        );
+    }
+
+    #[test]
+    pub fn test_a_star_matches_bfs() {
+        let map = maze();
+        let (start, goal) = (Point::new(0, 0), Point::new(7, 0));
+        let moves = |position: &Point| {
+            Direction::CARDINAL
+                .iter()
+                .map(|direction| (*position + *direction, 1))
+                .collect()
+        };
+        let heuristic = |position: &Point| position.manhattan_distance(goal) as u64;
+        let result = a_star(&map, start, goal, |c| *c, moves, heuristic).unwrap();
+        let expected = bfs(&map, start, goal, Connectivity::Four, |c| *c).unwrap();
+        assert_eq!(expected.distance, result.distance);
+    }
+}
@@ -1,39 +1,431 @@
-use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::str::FromStr;
 
-pub struct PeekableString {
-    chars: VecDeque<char>,
+/// A cursor over a string, walking it by byte offset without copying it.
+///
+/// Consumed text is kept around, so a parser can [`mark`](PeekableString::mark) a position,
+/// [`reset`](PeekableString::reset) back to it, or [`slice`](PeekableString::slice) what it consumed.
+#[derive(Clone)]
+pub struct PeekableString<'a> {
+    source: Cow<'a, str>,
+    position: Position,
 }
 
-impl PeekableString {
+/// A saved position to rewind a [`PeekableString`] to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mark {
+    position: Position,
+}
+
+/// Location in the input. `offset` is in bytes, `line` and `column` are 1-based (column in chars).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// What a parser was looking for, and the character it found instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub expected: String,
+    pub found: Option<char>,
+    pub position: Position,
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+impl Position {
+    fn start() -> Self {
+        Position {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn advance(&mut self, ch: char) {
+        self.offset += ch.len_utf8();
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+    }
+}
+
+impl<'a> PeekableString<'a> {
     pub fn pop(&mut self) -> Option<char> {
-        self.chars.pop_front()
+        let ch = self.peek()?;
+        self.position.advance(ch);
+        Some(ch)
     }
 
     pub fn peek(&self) -> Option<char> {
-        self.chars.front().cloned()
+        self.remaining().chars().next()
+    }
+
+    /// Returns up to the next `n` characters without consuming them.
+    pub fn peek_n(&self, n: usize) -> &str {
+        let remaining = self.remaining();
+        let end = remaining
+            .char_indices()
+            .nth(n)
+            .map_or(remaining.len(), |(idx, _)| idx);
+        &remaining[..end]
     }
 
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.remaining().starts_with(prefix)
+    }
+
+    /// Appends more input. The first call on a borrowed string copies it.
     pub fn push_str(&mut self, str: &str) {
-        for c in str.chars() {
-            self.chars.push_back(c)
-        }
+        self.source.to_mut().push_str(str);
     }
 
-    pub fn new(str: &str) -> PeekableString {
+    pub fn new(str: &'a str) -> PeekableString<'a> {
         PeekableString {
-            chars: str.chars().collect(),
+            source: Cow::Borrowed(str),
+            position: Position::start(),
         }
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining().is_empty()
+    }
+
+    pub fn remaining(&self) -> &str {
+        &self.source[self.position.offset..]
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn mark(&self) -> Mark {
+        Mark {
+            position: self.position,
+        }
+    }
+
+    pub fn reset(&mut self, mark: Mark) {
+        self.position = mark.position;
+    }
+
+    /// The text consumed since `mark`.
+    pub fn slice(&self, mark: Mark) -> &str {
+        &self.source[mark.position.offset..self.position.offset]
+    }
+
+    /// Runs `parser`, rewinding to the current position if it fails.
+    pub fn try_parse<T, F>(&mut self, parser: F) -> ParseResult<T>
+    where
+        F: FnOnce(&mut Self) -> ParseResult<T>,
+    {
+        let mark = self.mark();
+        let result = parser(self);
+        if result.is_err() {
+            self.reset(mark);
+        }
+        result
+    }
+
+    pub fn error<T>(&self, expected: &str) -> ParseResult<T> {
+        self.error_ahead(0, expected)
+    }
+
+    /// Builds an error located `skip` characters past the current position.
+    fn error_ahead<T>(&self, skip: usize, expected: &str) -> ParseResult<T> {
+        let mut position = self.position;
+        let mut remaining = self.remaining().chars();
+        for ch in remaining.by_ref().take(skip) {
+            position.advance(ch);
+        }
+        Err(ParseError {
+            expected: expected.to_string(),
+            found: remaining.next(),
+            position,
+        })
+    }
+
+    /// Consumes `expected` if the input starts with it, otherwise consumes nothing.
+    pub fn expect_str(&mut self, expected: &str) -> ParseResult<()> {
+        if !self.starts_with(expected) {
+            let matching = self
+                .remaining()
+                .chars()
+                .zip(expected.chars())
+                .take_while(|(a, b)| a == b)
+                .count();
+            return self.error_ahead(matching, &format!("{:?}", expected));
+        }
+        for _ in expected.chars() {
+            self.pop();
+        }
+        Ok(())
+    }
+
+    pub fn expect_end(&self) -> ParseResult<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            self.error("end of input")
+        }
+    }
+
+    pub fn take_while<F>(&mut self, predicate: F) -> &str
+    where
+        F: Fn(char) -> bool,
+    {
+        let start = self.mark();
+        while let Some(ch) = self.peek() {
+            if !predicate(ch) {
+                break;
+            }
+            self.pop();
+        }
+        self.slice(start)
+    }
+
+    /// Like [`take_while`](Self::take_while), but fails if not even one character matches.
+    pub fn take_while1<F>(&mut self, predicate: F, expected: &str) -> ParseResult<&str>
+    where
+        F: Fn(char) -> bool,
+    {
+        let start = self.mark();
+        if self.take_while(predicate).is_empty() {
+            self.error(expected)
+        } else {
+            Ok(self.slice(start))
+        }
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    /// Parses an optionally negative decimal integer, failing if it doesn't fit in `T`.
+    pub fn parse_int<T>(&mut self) -> ParseResult<T>
+    where
+        T: FromStr,
+    {
+        self.parse_number(true)
+    }
+
+    /// Parses a decimal integer without sign, failing if it doesn't fit in `T`.
+    pub fn parse_uint<T>(&mut self) -> ParseResult<T>
+    where
+        T: FromStr,
+    {
+        self.parse_number(false)
+    }
+
+    fn parse_number<T>(&mut self, allow_sign: bool) -> ParseResult<T>
+    where
+        T: FromStr,
+    {
+        let start = self.mark();
+        self.try_parse(|input| {
+            if allow_sign && input.peek() == Some('-') {
+                input.pop();
+            }
+            input.take_while1(|ch| ch.is_ascii_digit(), "a number")?;
+            input.slice(start).parse().or_else(|_| {
+                input.reset(start);
+                input.error(&format!(
+                    "a number fitting in {}",
+                    std::any::type_name::<T>()
+                ))
+            })
+        })
+    }
+
+    pub fn parse_u64(&mut self) -> ParseResult<u64> {
+        self.parse_uint()
+    }
+
+    pub fn parse_i64(&mut self) -> ParseResult<i64> {
+        self.parse_int()
+    }
 }
 
-impl From<&str> for PeekableString {
-    fn from(value: &str) -> Self {
+impl<'a> From<&'a str> for PeekableString<'a> {
+    fn from(value: &'a str) -> Self {
         PeekableString::new(value)
     }
 }
 
-impl From<&String> for PeekableString {
-    fn from(value: &String) -> Self {
+impl<'a> From<&'a String> for PeekableString<'a> {
+    fn from(value: &'a String) -> Self {
         PeekableString::new(value.as_str())
     }
 }
+
+impl ParseError {
+    /// What was expected and found, without the position.
+    pub fn summary(&self) -> String {
+        match self.found {
+            Some(ch) => format!("expected {}, found {:?}", self.expected, ch),
+            None => format!("expected {}, found end of input", self.expected),
+        }
+    }
+
+    /// Multi-line diagnostic showing the offending line of `source`, the text that was
+    /// parsed, with a caret under the failure point.
+    pub fn render(&self, source: &str) -> String {
+        let line_start = source[..self.position.offset]
+            .rfind('\n')
+            .map_or(0, |idx| idx + 1);
+        let line_text = source[line_start..].split('\n').next().unwrap_or("");
+        format!(
+            "{}\n{}--> line {}, column {}\n{}",
+            self.summary(),
+            " ".repeat(self.position.line.to_string().len()),
+            self.position.line,
+            self.position.column,
+            self.snippet(self.position.line, line_text)
+        )
+    }
+
+    /// Just the offending line and the caret, numbered `line_number`. For callers that parsed
+    /// one line of a bigger input and know where it came from.
+    pub fn snippet(&self, line_number: usize, line_text: &str) -> String {
+        snippet(line_number, line_text, self.position.column)
+    }
+}
+
+/// `line_text`, numbered `line_number`, with a caret under the 1-based `column`.
+pub fn snippet(line_number: usize, line_text: &str, column: usize) -> String {
+    let line_number = line_number.to_string();
+    let gutter = " ".repeat(line_number.len());
+    format!(
+        "{} |\n{} | {}\n{} | {}^",
+        gutter,
+        line_number,
+        line_text.trim_end_matches('\r'),
+        gutter,
+        " ".repeat(column - 1)
+    )
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.summary(),
+            self.position.line,
+            self.position.column
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::PeekableString;
+
+    #[test]
+    pub fn test_expect_str() {
+        let mut input = PeekableString::from("mul(1,2)");
+        assert!(input.expect_str("mud").is_err());
+        assert_eq!(Some('m'), input.peek());
+        assert!(input.expect_str("mul(").is_ok());
+        assert_eq!(Some('1'), input.peek());
+    }
+
+    #[test]
+    pub fn test_parse_numbers() {
+        let mut input = PeekableString::from("123,-45,x,99999999999");
+        assert_eq!(Ok(123), input.parse_u64());
+        input.expect_str(",").unwrap();
+        assert!(input.parse_u64().is_err());
+        assert_eq!(Ok(-45), input.parse_i64());
+        input.expect_str(",").unwrap();
+        let err = input.parse_i64().unwrap_err();
+        assert_eq!(Some('x'), err.found);
+        input.expect_str("x,").unwrap();
+        assert!(input.parse_int::<i32>().is_err());
+        assert_eq!(Ok(99999999999), input.parse_i64());
+        assert!(input.expect_end().is_ok());
+    }
+
+    #[test]
+    pub fn test_mark_and_reset() {
+        let mut input = PeekableString::from("do()don't()");
+        let start = input.mark();
+        input.expect_str("do()").unwrap();
+        assert_eq!(5, input.position().column);
+        input.reset(start);
+        assert_eq!(1, input.position().column);
+
+        input.expect_str("do()").unwrap();
+        let result = input.try_parse(|input| {
+            input.expect_str("do")?;
+            input.expect_str("()")
+        });
+        assert!(result.is_err());
+        assert_eq!(Some('d'), input.peek());
+        assert_eq!(5, input.position().column);
+        assert!(input.expect_str("don't()").is_ok());
+    }
+
+    #[test]
+    pub fn test_lookahead_and_slices() {
+        let text = String::from("çmul(12,3)");
+        let mut input = PeekableString::from(&text);
+        assert_eq!("çmu", input.peek_n(3));
+        assert_eq!(Some('ç'), input.pop());
+        assert!(input.starts_with("mul("));
+        assert_eq!("mul(12,3)", input.peek_n(100));
+
+        input.expect_str("mul(").unwrap();
+        let start = input.mark();
+        input.parse_u64().unwrap();
+        assert_eq!("12", input.slice(start));
+        assert_eq!(",3)", input.remaining());
+
+        input.push_str("mul(4,5)");
+        assert_eq!(",3)mul(4,5)", input.remaining());
+    }
+
+    #[test]
+    pub fn test_position_tracking() {
+        let mut input = PeekableString::from("ab\nçd");
+        input.expect_str("ab\n").unwrap();
+        input.pop();
+        let position = input.position();
+        assert_eq!((2, 2), (position.line, position.column));
+        assert_eq!(5, position.offset);
+    }
+
+    #[test]
+    pub fn test_render_diagnostic() {
+        let text = "47|53\n61x13\n";
+        let mut input = PeekableString::from(text);
+        input.expect_str("47|53\n61").unwrap();
+        let err = input.expect_str("|").unwrap_err();
+        assert_eq!(
+            "expected \"|\", found 'x'\n --> line 2, column 3\n  |\n2 | 61x13\n  |   ^",
+            err.render(text)
+        );
+        assert_eq!(
+            "expected \"|\", found 'x' at line 2, column 3",
+            err.to_string()
+        );
+
+        let mut input = PeekableString::from("mul(1;2)");
+        let err = input.expect_str("mul(1,").unwrap_err();
+        assert_eq!(6, err.position.column);
+        assert_eq!(Some(';'), err.found);
+        assert_eq!(
+            "expected \"mul(1,\", found ';' at line 1, column 6",
+            err.to_string()
+        );
+        assert_eq!("  |\n7 | mul(1;2)\n  |      ^", err.snippet(7, "mul(1;2)"));
+    }
+}
@@ -0,0 +1,344 @@
+use std::fs;
+use std::hash::Hash;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crate::input::InputSource;
+use crate::memoizer::Memoizer;
+use crate::read_file_to_str;
+
+/// Environment variable overriding where [`CacheFile::for_input`] keeps its files.
+pub const CACHE_DIR_ENV_VAR: &str = "AOC_CACHE_DIR";
+const DEFAULT_CACHE_DIR: &str = ".aoc-cache";
+const MAGIC: &[u8; 8] = b"AOCMEMO1";
+
+/// Values that can be written to and read back from a cache file.
+pub trait Persist: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    /// Reads a value from the front of `bytes`, advancing past it.
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self>;
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+fn take<'b>(bytes: &mut &'b [u8], count: usize) -> io::Result<&'b [u8]> {
+    if bytes.len() < count {
+        return Err(invalid_data("cache file is truncated"));
+    }
+    let (taken, rest) = bytes.split_at(count);
+    *bytes = rest;
+    Ok(taken)
+}
+
+macro_rules! persist_int {
+    ($($t:ty),*) => {
+        $(impl Persist for $t {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+                let taken = take(bytes, std::mem::size_of::<$t>())?;
+                Ok(<$t>::from_le_bytes(taken.try_into().unwrap()))
+            }
+        })*
+    };
+}
+
+persist_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl Persist for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        usize::try_from(u64::decode(bytes)?).map_err(|_| invalid_data("usize out of range"))
+    }
+}
+
+impl Persist for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u8).encode(out);
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        match u8::decode(bytes)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid_data("invalid bool")),
+        }
+    }
+}
+
+impl Persist for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u32).encode(out);
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        char::from_u32(u32::decode(bytes)?).ok_or_else(|| invalid_data("invalid char"))
+    }
+}
+
+impl Persist for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        let len = usize::decode(bytes)?;
+        String::from_utf8(take(bytes, len)?.to_vec()).map_err(|_| invalid_data("invalid UTF-8"))
+    }
+}
+
+impl<T> Persist for Option<T>
+where
+    T: Persist,
+{
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.is_some().encode(out);
+        if let Some(value) = self {
+            value.encode(out);
+        }
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        if bool::decode(bytes)? {
+            Ok(Some(T::decode(bytes)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<T> Persist for Vec<T>
+where
+    T: Persist,
+{
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        for value in self.iter() {
+            value.encode(out);
+        }
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        let len = usize::decode(bytes)?;
+        // don't trust the length for the allocation, the file may be corrupt
+        let mut values = Vec::with_capacity(len.min(bytes.len()));
+        for _ in 0..len {
+            values.push(T::decode(bytes)?);
+        }
+        Ok(values)
+    }
+}
+
+macro_rules! persist_tuple {
+    ($($name:ident),*) => {
+        impl<$($name),*> Persist for ($($name,)*)
+        where
+            $($name: Persist),*
+        {
+            #[allow(non_snake_case)]
+            fn encode(&self, out: &mut Vec<u8>) {
+                let ($($name,)*) = self;
+                $($name.encode(out);)*
+            }
+
+            fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+                Ok(($($name::decode(bytes)?,)*))
+            }
+        }
+    };
+}
+
+persist_tuple!(A);
+persist_tuple!(A, B);
+persist_tuple!(A, B, C);
+persist_tuple!(A, B, C, D);
+
+/// Where a [`Memoizer`]'s results for one puzzle input are saved between runs.
+///
+/// The file name includes a hash of the input, so editing the input starts a fresh cache.
+/// The hash is stored in the file as well and checked again on load.
+pub struct CacheFile {
+    path: PathBuf,
+    input_hash: u64,
+}
+
+impl CacheFile {
+    /// A cache named `name` for `input_text`, stored in `dir`.
+    pub fn new(dir: &Path, name: &str, input_text: &str) -> Self {
+        let input_hash = fnv1a(input_text.as_bytes());
+        CacheFile {
+            path: dir.join(format!("{}-{:016x}.cache", name, input_hash)),
+            input_hash,
+        }
+    }
+
+    /// A cache named `name` for the contents of `input`, stored in `AOC_CACHE_DIR`
+    /// (`.aoc-cache` by default). Stdin can't be read twice, so it isn't supported.
+    pub fn for_input(name: &str, input: &InputSource) -> io::Result<Self> {
+        if *input == InputSource::Stdin {
+            return Err(io::Error::new(
+                ErrorKind::Unsupported,
+                "can't cache results for input read from stdin",
+            ));
+        }
+        let dir = std::env::var(CACHE_DIR_ENV_VAR).unwrap_or(DEFAULT_CACHE_DIR.to_string());
+        Ok(Self::new(Path::new(&dir), name, &read_file_to_str(input)?))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the saved results, or an empty memoizer if there are none or they can't be read.
+    pub fn load<TInput, TOutput>(&self) -> Memoizer<TInput, TOutput>
+    where
+        TInput: Hash + Eq + Clone + Persist,
+        TOutput: Persist,
+    {
+        let mut memoizer = Memoizer::new();
+        match self.read_entries() {
+            Ok(entries) => {
+                crate::info!(
+                    "Loaded {} cached results from {:?}",
+                    entries.len(),
+                    self.path
+                );
+                memoizer.extend(entries);
+            }
+            Err(err) => crate::debug!("Not using cache {:?}: {}", self.path, err),
+        }
+        memoizer
+    }
+
+    /// Saves all of `memoizer`'s results. Caches of the same name for other inputs are kept,
+    /// see [`prune`](Self::prune).
+    pub fn save<TInput, TOutput>(&self, memoizer: &Memoizer<TInput, TOutput>) -> io::Result<()>
+    where
+        TInput: Hash + Eq + Clone + Persist,
+        TOutput: Persist,
+    {
+        let mut out = MAGIC.to_vec();
+        self.input_hash.encode(&mut out);
+        memoizer.len().encode(&mut out);
+        for (input, output) in memoizer.iter() {
+            input.encode(&mut out);
+            output.encode(&mut out);
+        }
+
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(dir)?;
+        fs::write(&self.path, out)?;
+        crate::info!("Saved {} results to {:?}", memoizer.len(), self.path);
+        Ok(())
+    }
+
+    /// Deletes the files saved under this cache's name for any other input.
+    pub fn prune(&self) -> io::Result<()> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let file_name = self.path.file_name().unwrap().to_string_lossy();
+        // strip "<16 hex digits>.cache"
+        let prefix = &file_name[..file_name.len() - 22];
+        for entry in fs::read_dir(dir)? {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if name != file_name
+                && name.len() == file_name.len()
+                && name.starts_with(prefix)
+                && name.ends_with(".cache")
+            {
+                fs::remove_file(dir.join(name))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_entries<TInput, TOutput>(&self) -> io::Result<Vec<(TInput, TOutput)>>
+    where
+        TInput: Persist,
+        TOutput: Persist,
+    {
+        let data = fs::read(&self.path)?;
+        let mut bytes = data.as_slice();
+        if take(&mut bytes, MAGIC.len())? != MAGIC {
+            return Err(invalid_data("not a cache file"));
+        }
+        if u64::decode(&mut bytes)? != self.input_hash {
+            return Err(invalid_data("cache is for a different input"));
+        }
+        let count = usize::decode(&mut bytes)?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            entries.push((TInput::decode(&mut bytes)?, TOutput::decode(&mut bytes)?));
+        }
+        if !bytes.is_empty() {
+            return Err(invalid_data("unexpected data after the cached results"));
+        }
+        Ok(entries)
+    }
+}
+
+/// FNV-1a, since std's hashers aren't guaranteed to be stable between Rust versions.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{CacheFile, Persist};
+    use crate::memoizer::Memoizer;
+
+    #[test]
+    pub fn test_encode_decode() {
+        let value = (vec![1i64, -2], String::from("ç"), Some('x'), 7usize);
+        let mut out = vec![];
+        value.encode(&mut out);
+        let mut bytes = out.as_slice();
+        assert_eq!(value, Persist::decode(&mut bytes).unwrap());
+        assert!(bytes.is_empty());
+
+        let mut truncated = &out[..out.len() - 1];
+        assert!(<(Vec<i64>, String, Option<char>, usize)>::decode(&mut truncated).is_err());
+    }
+
+    #[test]
+    pub fn test_save_and_reload() {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-test-{}", std::process::id()));
+        let double = |_: &mut Memoizer<Vec<u8>, u64>, input: &Vec<u8>| input.len() as u64 * 2;
+
+        let cache = CacheFile::new(&dir, "day0", "1 2 3\n");
+        let mut memoizer = cache.load();
+        assert!(memoizer.is_empty());
+        memoizer.calculate(double, vec![1, 2, 3]).unwrap();
+        cache.save(&memoizer).unwrap();
+
+        let mut reloaded: Memoizer<Vec<u8>, u64> = cache.load();
+        assert_eq!(Some(&6), reloaded.get(&[1, 2, 3][..]));
+
+        // a different input gets a fresh cache, next to the old one until pruned
+        let changed = CacheFile::new(&dir, "day0", "1 2 4\n");
+        let memoizer: Memoizer<Vec<u8>, u64> = changed.load();
+        assert!(memoizer.is_empty());
+        changed.save(&memoizer).unwrap();
+        assert!(cache.path().exists());
+        changed.prune().unwrap();
+        assert!(!cache.path().exists());
+        assert!(changed.path().exists());
+
+        // a corrupt file is ignored
+        fs::write(changed.path(), b"AOCMEMO1 garbage").unwrap();
+        assert!(changed.load::<Vec<u8>, u64>().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position on a [`Map2D`](crate::map2d::Map2D), or an offset between two positions.
+/// `y` grows downwards, like the rows of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// One of the 8 directions on a grid, in clockwise order starting from north (up).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+/// Which cells count as neighbors: the 4 sharing an edge, or the 8 sharing an edge or corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    Four,
+    Eight,
+}
+
+impl Point {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
+    }
+
+    pub fn manhattan_distance(&self, other: Point) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// Distance when diagonal moves are allowed, i.e. the number of king moves.
+    pub fn chebyshev_distance(&self, other: Point) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+}
+
+impl Direction {
+    /// The 4 directions without diagonals, clockwise from north.
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// All 8 directions, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// The step to take to move one cell in this direction.
+    pub fn offset(&self) -> Point {
+        match self {
+            Direction::North => Point::new(0, -1),
+            Direction::NorthEast => Point::new(1, -1),
+            Direction::East => Point::new(1, 0),
+            Direction::SouthEast => Point::new(1, 1),
+            Direction::South => Point::new(0, 1),
+            Direction::SouthWest => Point::new(-1, 1),
+            Direction::West => Point::new(-1, 0),
+            Direction::NorthWest => Point::new(-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        !Direction::CARDINAL.contains(self)
+    }
+
+    /// Turns 90 degrees clockwise.
+    pub fn turn_right(&self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// Turns 90 degrees counterclockwise.
+    pub fn turn_left(&self) -> Direction {
+        self.rotate(6)
+    }
+
+    pub fn reverse(&self) -> Direction {
+        self.rotate(4)
+    }
+
+    /// Turns clockwise by `eighths` of a full turn.
+    fn rotate(&self, eighths: usize) -> Direction {
+        Direction::ALL[(*self as usize + eighths) % 8]
+    }
+
+    /// Reads an arrow: `^ > v <`, or one of `↗ ↘ ↙ ↖` for the diagonals.
+    pub fn from_char(ch: char) -> Option<Direction> {
+        let direction = match ch {
+            '^' => Direction::North,
+            '↗' => Direction::NorthEast,
+            '>' => Direction::East,
+            '↘' => Direction::SouthEast,
+            'v' => Direction::South,
+            '↙' => Direction::SouthWest,
+            '<' => Direction::West,
+            '↖' => Direction::NorthWest,
+            _ => return None,
+        };
+        Some(direction)
+    }
+
+    /// The arrow [`from_char`](Self::from_char) reads back as this direction.
+    pub fn to_char(&self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::NorthEast => '↗',
+            Direction::East => '>',
+            Direction::SouthEast => '↘',
+            Direction::South => 'v',
+            Direction::SouthWest => '↙',
+            Direction::West => '<',
+            Direction::NorthWest => '↖',
+        }
+    }
+}
+
+impl Connectivity {
+    pub fn directions(&self) -> &'static [Direction] {
+        match self {
+            Connectivity::Four => &Direction::CARDINAL,
+            Connectivity::Eight => &Direction::ALL,
+        }
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((x, y): (i32, i32)) -> Self {
+        Point::new(x, y)
+    }
+}
+
+impl From<Point> for (i32, i32) {
+    fn from(point: Point) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl From<Direction> for Point {
+    fn from(direction: Direction) -> Self {
+        direction.offset()
+    }
+}
+
+impl<P> Add<P> for Point
+where
+    P: Into<Point>,
+{
+    type Output = Point;
+
+    fn add(self, other: P) -> Point {
+        let other = other.into();
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<P> AddAssign<P> for Point
+where
+    P: Into<Point>,
+{
+    fn add_assign(&mut self, other: P) {
+        *self = *self + other;
+    }
+}
+
+impl<P> Sub<P> for Point
+where
+    P: Into<Point>,
+{
+    type Output = Point;
+
+    fn sub(self, other: P) -> Point {
+        let other = other.into();
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<P> SubAssign<P> for Point
+where
+    P: Into<Point>,
+{
+    fn sub_assign(&mut self, other: P) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i32) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Point};
+
+    #[test]
+    pub fn test_point_arithmetic() {
+        let mut p = Point::new(2, 3);
+        assert_eq!(Point::new(2, 2), p + Direction::North);
+        assert_eq!(Point::new(5, 0), p + (3, -3));
+        assert_eq!(Point::new(1, 1), p - Point::new(1, 2));
+        assert_eq!(Point::new(-4, -6), -p * 2);
+        p += Direction::SouthWest;
+        assert_eq!(Point::new(1, 4), p);
+        assert_eq!((1, 4), p.into());
+    }
+
+    #[test]
+    pub fn test_distances() {
+        let a = Point::new(1, 1);
+        let b = Point::new(4, -1);
+        assert_eq!(5, a.manhattan_distance(b));
+        assert_eq!(3, a.chebyshev_distance(b));
+    }
+
+    #[test]
+    pub fn test_turns() {
+        assert_eq!(Direction::East, Direction::North.turn_right());
+        assert_eq!(Direction::North, Direction::West.turn_right());
+        assert_eq!(Direction::SouthWest, Direction::NorthWest.turn_left());
+        assert_eq!(Direction::SouthEast, Direction::NorthWest.reverse());
+        assert!(Direction::NorthEast.is_diagonal());
+        for direction in Direction::ALL {
+            assert_eq!(direction, direction.turn_right().turn_left());
+            assert_eq!(-direction.offset(), direction.reverse().offset());
+        }
+    }
+
+    #[test]
+    pub fn test_parse_arrows() {
+        let parsed: Vec<_> = "^>v<".chars().filter_map(Direction::from_char).collect();
+        assert_eq!(Direction::CARDINAL.to_vec(), parsed);
+        assert_eq!(None, Direction::from_char('x'));
+        assert_eq!('v', Direction::South.to_char());
+    }
+}
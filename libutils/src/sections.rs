@@ -0,0 +1,131 @@
+use crate::input::InputSource;
+use crate::lines::{lines_of, LineReader};
+use crate::FileProcessingErr;
+
+/// Reads an input made of sections separated by one or more blank lines.
+pub fn sections<S>(source: S) -> SectionReader
+where
+    S: Into<InputSource>,
+{
+    SectionReader {
+        lines: lines_of(source),
+    }
+}
+
+pub struct SectionReader {
+    lines: LineReader,
+}
+
+/// The lines of a section, each with its 0-based index in the whole input.
+type Section = Vec<(usize, String)>;
+
+impl SectionReader {
+    pub fn parse2<F1, F2, T1, T2, E>(
+        self,
+        mut parse_first: F1,
+        mut parse_second: F2,
+    ) -> Result<(Vec<T1>, Vec<T2>), FileProcessingErr<E>>
+    where
+        F1: FnMut(String) -> Result<T1, E>,
+        F2: FnMut(String) -> Result<T2, E>,
+    {
+        let file = self.lines.source_name().to_string();
+        let mut sections = self.split(2)?.into_iter();
+        Ok((
+            parse_section(&file, sections.next().unwrap(), &mut parse_first)?,
+            parse_section(&file, sections.next().unwrap(), &mut parse_second)?,
+        ))
+    }
+
+    fn split<E>(self, expected: usize) -> Result<Vec<Section>, FileProcessingErr<E>> {
+        let file = self.lines.source_name().to_string();
+        let mut sections: Vec<Section> = vec![];
+        let mut current: Section = vec![];
+        for (index, line) in self.lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                if !current.is_empty() {
+                    sections.push(std::mem::take(&mut current));
+                }
+            } else {
+                current.push((index, line));
+            }
+        }
+        if !current.is_empty() {
+            sections.push(current);
+        }
+
+        if sections.len() != expected {
+            return Err(FileProcessingErr::SectionCountError {
+                file,
+                expected,
+                found: sections.len(),
+            });
+        }
+        Ok(sections)
+    }
+}
+
+fn parse_section<F, TRes, E>(
+    file: &str,
+    section: Section,
+    parse: &mut F,
+) -> Result<Vec<TRes>, FileProcessingErr<E>>
+where
+    F: FnMut(String) -> Result<TRes, E>,
+{
+    section
+        .into_iter()
+        .map(|(index, line)| {
+            parse(line.clone()).map_err(|err| FileProcessingErr::processing(file, index, line, err))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sections;
+    use crate::input::InputSource;
+    use crate::FileProcessingErr;
+
+    #[test]
+    pub fn test_parse2() {
+        let source = InputSource::from_text("1|2\n3|4\n\n\n5,6,7\n8\n");
+        let (rules, lists) = sections(&source)
+            .parse2(
+                |line| Ok::<_, ()>(line.split('|').count()),
+                |line| Ok(line.split(',').count()),
+            )
+            .unwrap();
+        assert_eq!(vec![2, 2], rules);
+        assert_eq!(vec![3, 1], lists);
+    }
+
+    #[test]
+    pub fn test_wrong_section_count() {
+        let source = InputSource::from_text("1\n\n2\n\n3");
+        let result = sections(&source).parse2(Ok::<_, ()>, Ok);
+        match result {
+            Err(FileProcessingErr::SectionCountError {
+                expected, found, ..
+            }) => {
+                assert_eq!(2, expected);
+                assert_eq!(3, found);
+            }
+            _ => panic!("expected a section count error"),
+        }
+    }
+
+    #[test]
+    pub fn test_error_line_number() {
+        let source = InputSource::from_text("1\n\n2\nx");
+        let result =
+            sections(&source).parse2(|line| line.parse::<i32>(), |line| line.parse::<i32>());
+        match result {
+            Err(FileProcessingErr::ProcessingError { line_number, .. }) => {
+                assert_eq!(4, line_number)
+            }
+            _ => panic!("expected a processing error"),
+        }
+    }
+}
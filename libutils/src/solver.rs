@@ -0,0 +1,75 @@
+use std::fmt::Display;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use crate::input::InputSource;
+
+/// A solution for a single day: parses the input once and answers both parts from it.
+pub trait Solver {
+    type Input;
+    type Output: Display;
+    type Error: std::error::Error;
+
+    fn day(&self) -> u32;
+    fn parse(&self, input: &InputSource) -> Result<Self::Input, Self::Error>;
+    fn part1(&self, input: &Self::Input) -> Self::Output;
+    fn part2(&self, input: &Self::Input) -> Self::Output;
+}
+
+#[derive(Debug, Clone)]
+pub struct Answers {
+    pub day: u32,
+    pub part1: String,
+    pub part2: String,
+    pub elapsed: Duration,
+}
+
+/// Object-safe view of a [`Solver`], so solvers for different days can live in the same list.
+pub trait DynSolver {
+    fn day(&self) -> u32;
+    fn solve(&self, input: &InputSource) -> Result<Answers, String>;
+}
+
+impl<S> DynSolver for S
+where
+    S: Solver,
+{
+    fn day(&self) -> u32 {
+        Solver::day(self)
+    }
+
+    fn solve(&self, input: &InputSource) -> Result<Answers, String> {
+        let start = Instant::now();
+        let input = self.parse(input).map_err(|err| err.to_string())?;
+        let part1 = self.part1(&input).to_string();
+        let part2 = self.part2(&input).to_string();
+        Ok(Answers {
+            day: Solver::day(self),
+            part1,
+            part2,
+            elapsed: start.elapsed(),
+        })
+    }
+}
+
+/// Entry point for the per-day binaries: reads the input named by the first argument
+/// (`-` for stdin), or by `AOC_INPUT`, falling back to `input.txt`. Logging follows `AOC_LOG`.
+pub fn run_main<S>(solver: S) -> ExitCode
+where
+    S: Solver,
+{
+    crate::log::init_from_env();
+    let arg = std::env::args().nth(1);
+    let input = InputSource::resolve(arg.as_deref(), "input.txt");
+    match solver.solve(&input) {
+        Ok(answers) => {
+            println!("Part 1: {}", answers.part1);
+            println!("Part 2: {}", answers.part2);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_macro_input, Expr, FnArg, Ident, ItemFn, Pat, ReturnType, Token, Type};
+
+/// Caches the results of a plain (possibly recursive) function in a `libutils::memoizer::Memoizer`.
+///
+/// ```text
+/// #[memoize]
+/// fn fibonacci(n: u64) -> u64 {
+///     if n < 2 { n } else { fibonacci(n - 1) + fibonacci(n - 2) }
+/// }
+/// ```
+///
+/// By default the arguments (cloned) are the key and the cache lives for the whole thread.
+/// The body gets the arguments themselves, so it can consume them or declare them `mut`.
+/// Reference arguments need a `key` that doesn't borrow from them.
+/// Options:
+/// - `key = <expr>, key_type = <type>`: cache on a projection of the arguments instead,
+///   e.g. to leave out arguments that don't affect the result. The body still needs the
+///   arguments afterwards, so clone any the expression would move.
+/// - `scope = call`: use a fresh cache for every outside call, dropped when it returns.
+///
+/// Recursive calls in the body go straight to the cache being filled. Calling the function
+/// again from elsewhere while it is running (e.g. through a helper) is not supported with the
+/// thread scope, since the cache is already borrowed.
+#[proc_macro_attribute]
+pub fn memoize(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let options = parse_macro_input!(attr as Options);
+    let function = parse_macro_input!(item as ItemFn);
+    expand(options, function)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct Options {
+    key: Option<Expr>,
+    key_type: Option<Type>,
+    call_scope: bool,
+}
+
+impl Parse for Options {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = Options::default();
+        while !input.is_empty() {
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match name.to_string().as_str() {
+                "key" => options.key = Some(input.parse()?),
+                "key_type" => options.key_type = Some(input.parse()?),
+                "scope" => {
+                    let scope: Ident = input.parse()?;
+                    options.call_scope = match scope.to_string().as_str() {
+                        "call" => true,
+                        "thread" => false,
+                        _ => {
+                            return Err(syn::Error::new(
+                                scope.span(),
+                                "expected `call` or `thread`",
+                            ))
+                        }
+                    };
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        "expected `key`, `key_type` or `scope`",
+                    ))
+                }
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn expand(options: Options, function: ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let mut signature = function.sig.clone();
+    if !signature.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &signature.generics,
+            "#[memoize] does not support generic functions",
+        ));
+    }
+    let ReturnType::Type(_, output) = signature.output.clone() else {
+        return Err(syn::Error::new_spanned(
+            &signature,
+            "#[memoize] needs a function returning a value",
+        ));
+    };
+
+    let mut names = vec![];
+    let mut patterns = vec![];
+    let mut types = vec![];
+    for arg in signature.inputs.iter_mut() {
+        let FnArg::Typed(arg) = arg else {
+            return Err(syn::Error::new_spanned(
+                arg,
+                "#[memoize] does not support methods",
+            ));
+        };
+        let Pat::Ident(pat) = arg.pat.as_mut() else {
+            return Err(syn::Error::new_spanned(
+                &arg.pat,
+                "#[memoize] arguments must be plain identifiers",
+            ));
+        };
+        if options.key.is_none() && matches!(arg.ty.as_ref(), Type::Reference(_)) {
+            return Err(syn::Error::new_spanned(
+                &arg.ty,
+                "#[memoize] can't use a reference as part of the key, \
+                 give an owned one with `key = ..., key_type = ...`",
+            ));
+        }
+        names.push(pat.ident.clone());
+        // the arguments are moved into the body, which rebinds them with their `mut`
+        patterns.push(pat.clone());
+        pat.mutability = None;
+        types.push(arg.ty.as_ref().clone());
+    }
+
+    let (key, key_type) = match (options.key, options.key_type) {
+        (Some(key), Some(key_type)) => (quote!(#key), quote!(#key_type)),
+        (None, None) => (
+            quote!((#(::std::clone::Clone::clone(&#names),)*)),
+            quote!((#(#types,)*)),
+        ),
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`key` and `key_type` must be given together",
+            ))
+        }
+    };
+
+    let name = &function.sig.ident;
+    let implementation = format_ident!("__{}_memoized", name);
+    let memoizer = Ident::new("__memoizer", Span::call_site());
+    let mut body = function.block.clone();
+    RecursiveCalls {
+        name,
+        implementation: &implementation,
+        memoizer: &memoizer,
+    }
+    .visit_block_mut(&mut body);
+
+    let memoizer_type = quote!(::libutils::memoizer::Memoizer<#key_type, #output>);
+    let call = if options.call_scope {
+        quote!(#implementation(&mut <#memoizer_type>::new(), #(#names),*))
+    } else {
+        quote! {
+            ::std::thread_local! {
+                static MEMOIZER: ::std::cell::RefCell<#memoizer_type> =
+                    ::std::cell::RefCell::new(<#memoizer_type>::new());
+            }
+            MEMOIZER.with(|#memoizer| #implementation(&mut #memoizer.borrow_mut(), #(#names),*))
+        }
+    };
+
+    let attrs = &function.attrs;
+    let vis = &function.vis;
+    Ok(quote! {
+        #(#attrs)*
+        #vis #signature {
+            fn #implementation(#memoizer: &mut #memoizer_type, #(#names: #types),*) -> #output {
+                let __key = #key;
+                match #memoizer.calculate(
+                    move |#memoizer, _| {
+                        #(let #patterns = #names;)*
+                        #body
+                    },
+                    __key,
+                ) {
+                    ::std::result::Result::Ok(output) => ::std::clone::Clone::clone(output),
+                    ::std::result::Result::Err(_) => {
+                        ::std::panic!("{} called itself with arguments it is already computing", ::std::stringify!(#name))
+                    }
+                }
+            }
+            #call
+        }
+    })
+}
+
+/// Points recursive calls in the body at the implementation, sharing the memoizer.
+struct RecursiveCalls<'a> {
+    name: &'a Ident,
+    implementation: &'a Ident,
+    memoizer: &'a Ident,
+}
+
+impl<'a> VisitMut for RecursiveCalls<'a> {
+    fn visit_expr_call_mut(&mut self, call: &mut syn::ExprCall) {
+        visit_mut::visit_expr_call_mut(self, call);
+        let Expr::Path(path) = call.func.as_mut() else {
+            return;
+        };
+        if path.qself.is_none() && path.path.is_ident(self.name) {
+            path.path = self.implementation.clone().into();
+            let memoizer = self.memoizer;
+            let mut args: Punctuated<Expr, Token![,]> = Punctuated::new();
+            args.push(syn::parse_quote!(#memoizer));
+            args.extend(call.args.iter().cloned());
+            call.args = args;
+        }
+    }
+}